@@ -0,0 +1,86 @@
+use rand::Rng;
+
+use crate::{Board, GameResult, Move};
+
+/// Playouts longer than this many plies are stopped and counted as not won
+const MAX_PLAYOUT_LENGTH: usize = 200;
+
+/// Pick a move by playing random games after each of the available moves
+///
+/// For every available move, `playouts_per_move` games are played to the end with random moves,
+/// and the move whose games were won most often by the side to move is returned.
+/// Returns `None` if there are no moves to choose from.
+pub fn rollout_best_move<R: Rng, const N: usize>(
+    board: &Board<N>,
+    playouts_per_move: usize,
+    rng: &mut R,
+) -> Option<Move> {
+    let side = board.get_turn();
+    let mut best: Option<(usize, Move)> = None;
+
+    for m in board.available_moves() {
+        let (next, outcome) = match board.step(&m) {
+            Ok(step) => step,
+            Err(_) => continue,
+        };
+
+        let wins = if outcome == GameResult::Ongoing {
+            (0..playouts_per_move)
+                .filter(|_| playout(next.clone(), rng) == GameResult::win_for(side))
+                .count()
+        } else if outcome == GameResult::win_for(side) {
+            playouts_per_move
+        } else {
+            0
+        };
+
+        if best.as_ref().is_none_or(|(best_wins, _)| wins > *best_wins) {
+            best = Some((wins, m));
+        }
+    }
+
+    best.map(|(_, m)| m)
+}
+
+/// Play random moves until the game is over
+fn playout<R: Rng, const N: usize>(mut board: Board<N>, rng: &mut R) -> GameResult {
+    for _ in 0..MAX_PLAYOUT_LENGTH {
+        let m = match board.random_move(rng) {
+            Some(m) => m,
+            None => break,
+        };
+
+        if board.do_move(&m).is_err() {
+            break;
+        }
+
+        if board.result() != GameResult::Ongoing {
+            break;
+        }
+    }
+
+    board.result()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn rollout_finds_king_capture() {
+        let mut board = Board::new();
+        // surround the king on three sides, the attacker on (0, 3) can close the fourth
+        board.move_piece_uncheced(5, 5, 2, 2);
+        board.move_piece_uncheced(3, 0, 2, 1);
+        board.move_piece_uncheced(4, 0, 1, 2);
+        board.move_piece_uncheced(5, 0, 3, 2);
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let best = rollout_best_move(&board, 4, &mut rng).unwrap();
+
+        let (_, outcome) = board.step(&best).unwrap();
+        assert_eq!(outcome, GameResult::AttackerWin);
+    }
+}
@@ -1,11 +1,11 @@
 use std::{error::Error, fmt::Display};
 
-use crate::{CompactMove, HnefataflError, Turn};
+use crate::{CompactMove, GameResult, HnefataflError, Turn};
 
 use nom::{
     branch::alt,
     bytes::complete::{tag, take},
-    combinator::eof,
+    combinator::{eof, map_opt, map_res},
     IResult,
 };
 
@@ -14,9 +14,11 @@ use log::warn;
 #[derive(Debug)]
 pub enum CommandError {
     // TooFewBytes(got, expected)
-    TooFewBytes(u8, u8),
+    TooFewBytes(usize, usize),
     InvalidCommandKind(u8),
     ParseError,
+    /// A move list longer than the length prefix can hold
+    TooManyMoves(usize),
 }
 
 impl Display for CommandError {
@@ -29,12 +31,24 @@ impl Display for CommandError {
                 write!(f, "Invalid command kind: {}", kind)
             }
             CommandError::ParseError => write!(f, "Parse error"),
+            CommandError::TooManyMoves(count) => write!(f, "Too many moves: {}", count),
         }
     }
 }
 
 impl Error for CommandError {}
 
+/// Version of the binary wire format produced by [`Command::to_binary`].
+///
+/// Version 2 extended `IllegalMove` with the rejected move.
+/// Version 3 appended the outcome of the game to `MoveList`.
+/// Version 4 added `Undo`.
+/// Version 5 added the `Draw` outcome to `MoveList`.
+/// Version 6 widened the length of `MoveList` to two bytes, little endian.
+/// Version 7 added `Resign`, `DrawOffer` and `DrawResponse`.
+/// Version 8 added the number of captured pieces to moves, as a shield wall captures several.
+pub const PROTOCOL_VERSION: u8 = 8;
+
 #[repr(u8)]
 enum CommandKind {
     Move = 0,
@@ -45,6 +59,10 @@ enum CommandKind {
     ColorSelect = 5,
     Reset = 6,
     Observer = 7,
+    Undo = 8,
+    Resign = 9,
+    DrawOffer = 10,
+    DrawResponse = 11,
 
     IllegalCommand = 255,
 }
@@ -52,9 +70,12 @@ enum CommandKind {
 /// Move contains a move
 /// A user sends move to server, then server sends move to everybody
 ///
-/// IllegalMove contains an error
+/// IllegalMove contains an error, and optionally the move that was rejected
+/// The error is sent as one byte, its code from [`HnefataflError::to_code`]
+/// The move lets a client that pipelines moves know which one failed
 ///
-/// MoveList contains a list of moves (usually as a response to request_history)
+/// MoveList contains a list of moves (usually as a response to request_history),
+/// followed by the outcome of the game after those moves
 ///
 /// Username contains a string, the username
 /// A user sends username to server, then server sends username to everybody
@@ -74,18 +95,35 @@ enum CommandKind {
 /// In establishing phase, server might respond with observer instead of ColorSelect if there are
 /// already two players
 ///
+/// Undo contains the number of plies to take back
+/// A user sends undo to server, then server takes back the moves and sends move_list to everybody
+///
+/// Resign contains no data
+/// A user sends resign to server, then server sends resign to everybody
+/// The player who resigned loses the game
+///
+/// DrawOffer contains no data
+/// A user sends draw_offer to server, then server sends draw_offer to the opponent
+///
+/// DrawResponse contains whether the draw is accepted, as one byte that is 1 or 0
+/// The opponent answers a draw_offer with draw_response, then server sends it to everybody
+///
 /// IllegalCommand contains no data
 /// Usual response when receiving an illegal command
 #[derive(Clone, Debug, PartialEq)]
 pub enum Command {
     Move(CompactMove),
-    IllegalMove(HnefataflError),
-    MoveList(Vec<CompactMove>),
+    IllegalMove(HnefataflError, Option<CompactMove>),
+    MoveList(Vec<CompactMove>, GameResult),
     Username(String),
     RequestHistory,
     ColorSelect(Turn),
     Reset,
     Observer,
+    Undo(u8),
+    Resign,
+    DrawOffer,
+    DrawResponse(bool),
 
     IllegalCommand,
 }
@@ -101,11 +139,10 @@ fn parse_compact_move(input: &[u8]) -> IResult<&[u8], CompactMove> {
 }
 
 /// Parse a string that is prefixed by its length.
+/// Strings that are not valid UTF-8 are rejected.
 fn parse_string(input: &[u8]) -> IResult<&[u8], String> {
     let (input, length) = take(1usize)(input)?;
-    let (input, name) = take(length[0])(input)?;
-
-    let name = unsafe { std::str::from_utf8_unchecked(name) };
+    let (input, name) = map_res(take(length[0]), std::str::from_utf8)(input)?;
 
     Ok((input, name.to_string()))
 }
@@ -119,26 +156,34 @@ fn parse_move(input: &[u8]) -> IResult<&[u8], Command> {
 
 fn parse_illegal_move(input: &[u8]) -> IResult<&[u8], Command> {
     let (input, _) = tag(&[CommandKind::IllegalMove as u8])(input)?;
-    let (input, error) = take(1usize)(input)?;
+    let (input, error) = map_res(take(1usize), |b: &[u8]| HnefataflError::try_from(b[0]))(input)?;
+    let (input, has_move) = take(1usize)(input)?;
+    let (input, compact_move) = parse_compact_move(input)?;
 
-    let error = unsafe { std::mem::transmute(error[0]) };
+    let compact_move = match has_move[0] {
+        0 => None,
+        _ => Some(compact_move),
+    };
 
-    Ok((input, Command::IllegalMove(error)))
+    Ok((input, Command::IllegalMove(error, compact_move)))
 }
 
 fn parse_move_list(input: &[u8]) -> IResult<&[u8], Command> {
     let (input, _) = tag(&[CommandKind::MoveList as u8])(input)?;
-    let (mut input, num) = take(1usize)(input)?;
+    let (mut input, num) = take(2usize)(input)?;
+    let num = u16::from_le_bytes([num[0], num[1]]);
 
-    let mut moves = Vec::with_capacity(num[0] as usize);
+    let mut moves = Vec::with_capacity(num as usize);
 
-    for _ in 0..num[0] {
+    for _ in 0..num {
         let (i, m) = parse_compact_move(input)?;
         input = i;
         moves.push(m);
     }
 
-    Ok((input, Command::MoveList(moves)))
+    let (input, outcome) = map_opt(take(1usize), |b: &[u8]| GameResult::from_code(b[0]))(input)?;
+
+    Ok((input, Command::MoveList(moves, outcome)))
 }
 
 fn parse_initiate(input: &[u8]) -> IResult<&[u8], Command> {
@@ -156,9 +201,7 @@ fn parse_request_history(input: &[u8]) -> IResult<&[u8], Command> {
 
 fn parse_color_select(input: &[u8]) -> IResult<&[u8], Command> {
     let (input, _) = tag(&[CommandKind::ColorSelect as u8])(input)?;
-    let (input, turn) = take(1usize)(input)?;
-
-    let turn = unsafe { std::mem::transmute(turn[0]) };
+    let (input, turn) = map_res(take(1usize), |b: &[u8]| Turn::try_from(b[0]))(input)?;
 
     Ok((input, Command::ColorSelect(turn)))
 }
@@ -175,6 +218,36 @@ fn parse_observer(input: &[u8]) -> IResult<&[u8], Command> {
     Ok((input, Command::Observer))
 }
 
+fn parse_undo(input: &[u8]) -> IResult<&[u8], Command> {
+    let (input, _) = tag(&[CommandKind::Undo as u8])(input)?;
+    let (input, plies) = take(1usize)(input)?;
+
+    Ok((input, Command::Undo(plies[0])))
+}
+
+fn parse_resign(input: &[u8]) -> IResult<&[u8], Command> {
+    let (input, _) = tag(&[CommandKind::Resign as u8])(input)?;
+
+    Ok((input, Command::Resign))
+}
+
+fn parse_draw_offer(input: &[u8]) -> IResult<&[u8], Command> {
+    let (input, _) = tag(&[CommandKind::DrawOffer as u8])(input)?;
+
+    Ok((input, Command::DrawOffer))
+}
+
+fn parse_draw_response(input: &[u8]) -> IResult<&[u8], Command> {
+    let (input, _) = tag(&[CommandKind::DrawResponse as u8])(input)?;
+    let (input, accepted) = map_opt(take(1usize), |b: &[u8]| match b[0] {
+        0 => Some(false),
+        1 => Some(true),
+        _ => None,
+    })(input)?;
+
+    Ok((input, Command::DrawResponse(accepted)))
+}
+
 fn parse_illegal_command(input: &[u8]) -> IResult<&[u8], Command> {
     let (input, _) = tag(&[CommandKind::IllegalCommand as u8])(input)?;
 
@@ -191,6 +264,10 @@ fn parse_command(input: &[u8]) -> IResult<&[u8], Command> {
         parse_color_select,
         parse_reset,
         parse_observer,
+        parse_undo,
+        parse_resign,
+        parse_draw_offer,
+        parse_draw_response,
         parse_illegal_command,
     ))(input)?;
     let (input, _) = eof(input)?;
@@ -209,52 +286,90 @@ impl Command {
         }
     }
 
+    /// Read one command from the front of a stream buffer
+    ///
+    /// Every command in the stream is prefixed by its length as two bytes, big endian, see
+    /// [`Command::write_framed`]. Returns `Ok(None)` when the buffer does not hold a whole command
+    /// yet. Otherwise the command is removed from the buffer, also if it fails to parse.
+    pub fn read_framed(buf: &mut Vec<u8>) -> Result<Option<Command>, CommandError> {
+        if buf.len() < 2 {
+            return Ok(None);
+        }
+
+        let length = u16::from_be_bytes([buf[0], buf[1]]) as usize;
+        if buf.len() < 2 + length {
+            return Ok(None);
+        }
+
+        let frame = buf.drain(..2 + length).collect::<Vec<_>>();
+        Command::from_binary(&frame[2..]).map(Some)
+    }
+
+    /// Encode the command prefixed by its length, to be read by [`Command::read_framed`]
+    ///
+    /// Panics if the command is longer than `u16::MAX` bytes
+    pub fn write_framed(&self) -> Vec<u8> {
+        let bytes = self.to_binary_vec();
+        let length = u16::try_from(bytes.len()).expect("command too long for a frame");
+
+        let mut framed = length.to_be_bytes().to_vec();
+        framed.extend(bytes);
+        framed
+    }
+
+    /// Panics if the command can not be encoded, see [`Command::to_binary`]
     pub fn to_binary_vec(&self) -> Vec<u8> {
-        let mut bytes = [0u8; 256];
+        // the longest commands besides move lists are usernames, of at most 255 bytes
+        let capacity = match self {
+            Command::MoveList(moves, _) => 4 + moves.len() * 4,
+            _ => 257,
+        };
+        let mut bytes = vec![0u8; capacity];
         let length = self.to_binary(&mut bytes).unwrap();
-        bytes[0..length].to_vec()
+        bytes.truncate(length);
+        bytes
     }
 
     pub fn to_binary(&self, bytes: &mut [u8]) -> Result<usize, CommandError> {
         match self {
             Command::Move(compact_move) => {
                 if bytes.len() < 5 {
-                    return Err(CommandError::TooFewBytes(bytes.len() as u8, 5));
+                    return Err(CommandError::TooFewBytes(bytes.len(), 5));
                 }
                 bytes[0] = CommandKind::Move as u8;
                 let b: [u8; 4] = (*compact_move).into();
                 bytes[1..5].copy_from_slice(&b);
                 Ok(5)
             }
-            Command::IllegalMove(error) => {
-                if bytes.len() < 2 {
-                    return Err(CommandError::TooFewBytes(bytes.len() as u8, 2));
+            Command::IllegalMove(error, compact_move) => {
+                if bytes.len() < 7 {
+                    return Err(CommandError::TooFewBytes(bytes.len(), 7));
                 }
                 bytes[0] = CommandKind::IllegalMove as u8;
-                bytes[1] = *error as u8;
-                Ok(2)
+                bytes[1] = error.to_code();
+                bytes[2] = compact_move.is_some() as u8;
+                let b: [u8; 4] = compact_move.map(Into::into).unwrap_or([0; 4]);
+                bytes[3..7].copy_from_slice(&b);
+                Ok(7)
             }
-            Command::MoveList(moves) => {
-                if bytes.len() < 2 + moves.len() * 4 {
-                    return Err(CommandError::TooFewBytes(
-                        bytes.len() as u8,
-                        2 + moves.len() as u8 * 4,
-                    ));
+            Command::MoveList(moves, outcome) => {
+                let count = u16::try_from(moves.len())
+                    .map_err(|_| CommandError::TooManyMoves(moves.len()))?;
+                if bytes.len() < 4 + moves.len() * 4 {
+                    return Err(CommandError::TooFewBytes(bytes.len(), 4 + moves.len() * 4));
                 }
                 bytes[0] = CommandKind::MoveList as u8;
-                bytes[1] = moves.len() as u8;
+                bytes[1..3].copy_from_slice(&count.to_le_bytes());
                 for (i, m) in moves.iter().enumerate() {
                     let b: [u8; 4] = (*m).into();
-                    bytes[2 + i * 4..2 + (i + 1) * 4].copy_from_slice(&b);
+                    bytes[3 + i * 4..3 + (i + 1) * 4].copy_from_slice(&b);
                 }
-                Ok(2 + moves.len() * 4)
+                bytes[3 + moves.len() * 4] = outcome.to_code();
+                Ok(4 + moves.len() * 4)
             }
             Command::Username(name) => {
                 if bytes.len() < 2 + name.len() {
-                    return Err(CommandError::TooFewBytes(
-                        bytes.len() as u8,
-                        2 + name.len() as u8,
-                    ));
+                    return Err(CommandError::TooFewBytes(bytes.len(), 2 + name.len()));
                 }
                 bytes[0] = CommandKind::Username as u8;
                 bytes[1] = name.len() as u8;
@@ -263,36 +378,66 @@ impl Command {
             }
             Command::RequestHistory => {
                 if bytes.is_empty() {
-                    return Err(CommandError::TooFewBytes(bytes.len() as u8, 1));
+                    return Err(CommandError::TooFewBytes(bytes.len(), 1));
                 }
                 bytes[0] = CommandKind::RequestHistory as u8;
                 Ok(1)
             }
             Command::ColorSelect(turn) => {
                 if bytes.len() < 2 {
-                    return Err(CommandError::TooFewBytes(bytes.len() as u8, 2));
+                    return Err(CommandError::TooFewBytes(bytes.len(), 2));
                 }
                 bytes[0] = CommandKind::ColorSelect as u8;
-                bytes[1] = *turn as u8;
+                bytes[1] = turn.to_u8();
                 Ok(2)
             }
             Command::Reset => {
                 if bytes.is_empty() {
-                    return Err(CommandError::TooFewBytes(bytes.len() as u8, 1));
+                    return Err(CommandError::TooFewBytes(bytes.len(), 1));
                 }
                 bytes[0] = CommandKind::Reset as u8;
                 Ok(1)
             }
             Command::Observer => {
                 if bytes.is_empty() {
-                    return Err(CommandError::TooFewBytes(bytes.len() as u8, 1));
+                    return Err(CommandError::TooFewBytes(bytes.len(), 1));
                 }
                 bytes[0] = CommandKind::Observer as u8;
                 Ok(1)
             }
+            Command::Undo(plies) => {
+                if bytes.len() < 2 {
+                    return Err(CommandError::TooFewBytes(bytes.len(), 2));
+                }
+                bytes[0] = CommandKind::Undo as u8;
+                bytes[1] = *plies;
+                Ok(2)
+            }
+            Command::Resign => {
+                if bytes.is_empty() {
+                    return Err(CommandError::TooFewBytes(bytes.len(), 1));
+                }
+                bytes[0] = CommandKind::Resign as u8;
+                Ok(1)
+            }
+            Command::DrawOffer => {
+                if bytes.is_empty() {
+                    return Err(CommandError::TooFewBytes(bytes.len(), 1));
+                }
+                bytes[0] = CommandKind::DrawOffer as u8;
+                Ok(1)
+            }
+            Command::DrawResponse(accepted) => {
+                if bytes.len() < 2 {
+                    return Err(CommandError::TooFewBytes(bytes.len(), 2));
+                }
+                bytes[0] = CommandKind::DrawResponse as u8;
+                bytes[1] = *accepted as u8;
+                Ok(2)
+            }
             Command::IllegalCommand => {
                 if bytes.is_empty() {
-                    return Err(CommandError::TooFewBytes(bytes.len() as u8, 1));
+                    return Err(CommandError::TooFewBytes(bytes.len(), 1));
                 }
                 bytes[0] = CommandKind::IllegalCommand as u8;
                 Ok(1)
@@ -325,20 +470,134 @@ mod tests {
     #[test]
     fn test_moves() {
         test_to_from::<5>(Command::Move(Move::from(0, 0, 1, 0).unwrap().compact()));
-        test_to_from::<2>(Command::IllegalMove(HnefataflError::IllegalMove));
-
-        test_to_from::<{ 2 + 4 * 4 }>(Command::MoveList(vec![
-            Move::from(0, 0, 1, 0).unwrap().compact(),
-            Move::from(0, 0, 2, 0).unwrap().compact(),
-            Move::from(0, 0, 3, 0).unwrap().compact(),
-            Move::from(0, 0, 4, 0).unwrap().compact(),
-        ]));
+        test_to_from::<7>(Command::IllegalMove(HnefataflError::IllegalMove, None));
+
+        test_to_from::<{ 4 + 4 * 4 }>(Command::MoveList(
+            vec![
+                Move::from(0, 0, 1, 0).unwrap().compact(),
+                Move::from(0, 0, 2, 0).unwrap().compact(),
+                Move::from(0, 0, 3, 0).unwrap().compact(),
+                Move::from(0, 0, 4, 0).unwrap().compact(),
+            ],
+            GameResult::Ongoing,
+        ));
         test_to_from::<6>(Command::Username("test".to_string()));
         test_to_from::<1>(Command::RequestHistory);
         test_to_from::<2>(Command::ColorSelect(Turn::White));
+        test_to_from::<2>(Command::ColorSelect(Turn::Black));
         test_to_from::<1>(Command::Reset);
         test_to_from::<1>(Command::Observer);
+        test_to_from::<2>(Command::Undo(1));
+        test_to_from::<2>(Command::Undo(2));
+        test_to_from::<1>(Command::Resign);
+        test_to_from::<1>(Command::DrawOffer);
+        test_to_from::<2>(Command::DrawResponse(true));
+        test_to_from::<2>(Command::DrawResponse(false));
 
         test_to_from::<1>(Command::IllegalCommand);
     }
+
+    #[test]
+    fn test_move_list_with_outcome() {
+        let mut m = Move::from(3, 5, 0, 5).unwrap();
+        m.set_win();
+
+        test_to_from::<{ 4 + 4 * 2 }>(Command::MoveList(
+            vec![Move::from(0, 7, 4, 7).unwrap().compact(), m.compact()],
+            GameResult::DefenderWin,
+        ));
+        test_to_from::<4>(Command::MoveList(vec![], GameResult::AttackerWin));
+        test_to_from::<4>(Command::MoveList(vec![], GameResult::Draw));
+
+        // unknown outcome
+        assert!(Command::from_binary(&[CommandKind::MoveList as u8, 0, 0, 9]).is_err());
+    }
+
+    #[test]
+    fn test_illegal_move_error_codes() {
+        let bytes = Command::IllegalMove(HnefataflError::IllegalMove, None).to_binary_vec();
+        assert_eq!(bytes[1], 9);
+
+        for error in (0..=u8::MAX).filter_map(|code| HnefataflError::try_from(code).ok()) {
+            test_to_from::<7>(Command::IllegalMove(error, None));
+        }
+
+        // unknown error code
+        assert!(
+            Command::from_binary(&[CommandKind::IllegalMove as u8, 200, 0, 0, 0, 0, 0]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_framing() {
+        let first = Command::Move(Move::from(0, 3, 2, 3).unwrap().compact());
+        let second = Command::Username("teo".to_string());
+
+        let framed = first.write_framed();
+        assert_eq!(framed[..2], [0, 5]);
+
+        let mut buf = framed[..4].to_vec();
+        assert!(Command::read_framed(&mut buf).unwrap().is_none());
+        assert_eq!(buf.len(), 4);
+
+        buf.extend(&framed[4..]);
+        buf.extend(second.write_framed());
+        buf.extend(&first.write_framed()[..1]);
+        assert_eq!(Command::read_framed(&mut buf).unwrap(), Some(first));
+        assert_eq!(Command::read_framed(&mut buf).unwrap(), Some(second));
+        assert!(Command::read_framed(&mut buf).unwrap().is_none());
+        assert_eq!(buf.len(), 1);
+
+        // a frame that does not parse is consumed
+        let mut buf = vec![0, 2, CommandKind::ColorSelect as u8, 7, 0, 1, 4];
+        assert!(Command::read_framed(&mut buf).is_err());
+        assert_eq!(
+            Command::read_framed(&mut buf).unwrap(),
+            Some(Command::RequestHistory)
+        );
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_long_move_list() {
+        let moves = (0..300)
+            .map(|i| Move::from(0, i % 10, 10, i % 10).unwrap().compact())
+            .collect();
+        test_to_from::<{ 4 + 4 * 300 }>(Command::MoveList(moves, GameResult::Ongoing));
+
+        let moves = vec![Move::from(0, 0, 1, 0).unwrap().compact(); u16::MAX as usize + 1];
+        assert!(matches!(
+            Command::MoveList(moves, GameResult::Ongoing).to_binary(&mut []),
+            Err(CommandError::TooManyMoves(65536))
+        ));
+    }
+
+    #[test]
+    fn test_username_utf8() {
+        // "tær" is four bytes
+        test_to_from::<6>(Command::Username("tær".to_string()));
+
+        assert!(Command::from_binary(&[CommandKind::Username as u8, 2, 0xc3, 0x28]).is_err());
+        assert!(Command::from_binary(&[CommandKind::Username as u8, 1, 0xff]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_draw_response() {
+        assert!(Command::from_binary(&[CommandKind::DrawResponse as u8, 2]).is_err());
+        assert!(Command::from_binary(&[CommandKind::DrawResponse as u8]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_color() {
+        assert!(Command::from_binary(&[CommandKind::ColorSelect as u8, 5]).is_err());
+        assert!(Command::from_binary(&[CommandKind::ColorSelect as u8, 255]).is_err());
+    }
+
+    #[test]
+    fn test_illegal_move_with_move() {
+        test_to_from::<7>(Command::IllegalMove(
+            HnefataflError::PieceInTheWay,
+            Some(Move::from(0, 7, 5, 7).unwrap().compact()),
+        ));
+    }
 }
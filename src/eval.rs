@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+
+use crate::{Board, Direction, GameResult, Turn};
+
+/// The score of a position where the king has been captured
+const KING_CAPTURED: i32 = -10_000;
+
+/// The throne of the standard board
+const THRONE: (i32, i32) = (5, 5);
+
+/// How far from the throne attackers count towards cohesion, in tiles along either axis
+const THRONE_REACH: i32 = 3;
+
+/// A heuristic evaluation of a position in standard hnefatafl, for use with [`crate::search`]
+///
+/// Positive scores favor the defenders (white), negative scores the attackers (black).
+/// The score is made up of
+/// - the material difference, where a defender is worth two attackers,
+/// - how close the king is to a corner,
+/// - the number of rows and columns the king can follow to the edge of the board,
+/// - how many attackers around the throne stand next to another attacker, as attackers in a
+///   group there are harder to capture and close in on the king together.
+pub fn standard(board: &Board) -> i32 {
+    match board.result() {
+        GameResult::DefenderWin => return -KING_CAPTURED,
+        GameResult::AttackerWin => return KING_CAPTURED,
+        GameResult::Draw => return 0,
+        GameResult::Ongoing => {}
+    }
+
+    let (x, y) = match board.king_position() {
+        Some(king) => king,
+        None => return KING_CAPTURED,
+    };
+
+    let material = 2 * board.material(Turn::White) - board.material(Turn::Black);
+
+    let corner_distance = board.king_distance_to_nearest_corner().unwrap_or(0);
+
+    use Direction::*;
+    let escape_lines = [Up, Right, Down, Left]
+        .into_iter()
+        .filter(|&direction| {
+            board
+                .ray(x, y, direction)
+                .last()
+                .is_some_and(|(_, piece)| piece.is_none())
+        })
+        .count() as i32;
+
+    let attackers = board
+        .pieces_of(Turn::Black)
+        .map(|(x, y, _)| (x, y))
+        .collect::<HashSet<_>>();
+    let cohesion = attackers
+        .iter()
+        .filter(|&&(x, y)| {
+            (x - THRONE.0).abs() <= THRONE_REACH && (y - THRONE.1).abs() <= THRONE_REACH
+        })
+        .filter(|&&(x, y)| {
+            (-1..=1)
+                .flat_map(|i| (-1..=1).map(move |j| (i, j)))
+                .any(|(i, j)| (i, j) != (0, 0) && attackers.contains(&(x + i, y + j)))
+        })
+        .count() as i32;
+
+    10 * material - 5 * corner_distance + 15 * escape_lines - 2 * cohesion
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BoardBuilder, Piece};
+
+    #[test]
+    fn attackers_grouped_at_throne_are_good_for_attackers() {
+        let board = |attackers: [(i32, i32); 2]| {
+            let mut builder = BoardBuilder::new().piece(Piece::King, 8, 8);
+            for (x, y) in attackers {
+                builder = builder.piece(Piece::Attacker, x, y);
+            }
+            builder.build().unwrap()
+        };
+
+        let at_throne = board([(4, 3), (5, 3)]);
+        let at_edge = board([(1, 0), (2, 0)]);
+        let apart = board([(3, 3), (5, 3)]);
+
+        assert!(standard(&at_throne) < standard(&at_edge));
+        assert_eq!(standard(&at_edge), standard(&apart));
+    }
+
+    #[test]
+    fn king_near_corner_is_good_for_defenders() {
+        let start = Board::new();
+
+        let mut near_corner = Board::new();
+        near_corner.move_piece_uncheced(5, 5, 0, 1);
+
+        assert!(standard(&near_corner) > standard(&start));
+
+        // it works with the search
+        let mut board = Board::new();
+        board.move_piece_uncheced(5, 5, 2, 2);
+        board.move_piece_uncheced(3, 0, 2, 1);
+        board.move_piece_uncheced(4, 0, 1, 2);
+        board.move_piece_uncheced(5, 0, 3, 2);
+        let (best, _) = crate::search::alpha_beta(&board, 2, &standard);
+        let (next, outcome) = board.step(&best.unwrap()).unwrap();
+        assert_eq!(outcome, GameResult::AttackerWin);
+        assert_eq!(standard(&next), KING_CAPTURED);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{Board, CompactMove, HnefataflError, Move};
+
+/// A game from the starting position, with the moves played so far
+#[derive(Debug, Clone, Default)]
+pub struct Game {
+    board: Board,
+    moves: Vec<CompactMove>,
+}
+
+impl Game {
+    /// Start a new game from the starting position
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The board after the moves played so far
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// The moves played so far, as performed, with their captures and wins
+    pub fn moves(&self) -> &[CompactMove] {
+        &self.moves
+    }
+
+    /// Play a move, and add it to the moves of the game if it is legal
+    pub fn play(&mut self, m: &Move) -> Result<CompactMove, HnefataflError> {
+        let performed = self.board.do_move(m)?;
+        self.moves.push(performed);
+        Ok(performed)
+    }
+
+    /// Write the moves of the game as numbered pairs in short notation
+    ///
+    /// One line per pair of moves, like `1. Ad9 Dc8`, see [`Board::disambiguate`]
+    pub fn to_transcript(&self) -> String {
+        let mut board = Board::new();
+        let moves = self
+            .moves
+            .iter()
+            .map(|&m| {
+                let m: Move = m.into();
+                let written = board.disambiguate(&m);
+                // the moves were legal when they were played
+                board.do_move(&m).unwrap();
+                written
+            })
+            .collect::<Vec<_>>();
+
+        moves
+            .chunks(2)
+            .enumerate()
+            .map(|(i, pair)| format!("{}. {}", i + 1, pair.join(" ")))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Replay a game written by [`Game::to_transcript`] from the starting position
+    ///
+    /// The move numbers must count up from 1, and every move must be legal. Moves in algebraic
+    /// notation, like `d11-d9`, are read as well, see [`Move::from_algebraic`].
+    pub fn from_transcript(s: &str) -> Result<Self, HnefataflError> {
+        let mut game = Self::new();
+
+        for token in s.split_whitespace() {
+            match token.strip_suffix('.') {
+                Some(number) => {
+                    let expected = game.moves.len() / 2 + 1;
+                    if !game.moves.len().is_multiple_of(2) || number.parse() != Ok(expected) {
+                        return Err(HnefataflError::InvalidNotation);
+                    }
+                }
+                None if token.contains(['-', 'x']) => {
+                    game.play(&Move::from_algebraic(token)?)?;
+                }
+                None => {
+                    let m = game.board.parse_move(token)?;
+                    game.play(&m)?;
+                }
+            }
+        }
+
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcript_round_trip() {
+        let mut game = Game::new();
+        for (x, y, to_x, to_y) in [
+            (3, 0, 3, 2),
+            (5, 3, 2, 3),
+            (0, 7, 4, 7),
+            (7, 5, 7, 8),
+            (4, 7, 2, 7),
+        ] {
+            game.play(&Move::from(x, y, to_x, to_y).unwrap()).unwrap();
+        }
+        assert!(game.play(&Move::from(0, 0, 0, 1).unwrap()).is_err());
+        assert_eq!(game.moves().len(), 5);
+
+        let transcript = game.to_transcript();
+        // the attackers on a4 and i4 can both reach e4
+        assert_eq!(transcript, "1. Ad9 Dc8\n2. Aae4 Dh3\n3. Ac4");
+
+        let replayed = Game::from_transcript(&transcript).unwrap();
+        assert_eq!(replayed.board(), game.board());
+        assert_eq!(replayed.moves(), game.moves());
+
+        // algebraic notation is read as well
+        let replayed = Game::from_transcript("1. d11-d9 f8-c8\n2. a4-e4 h6-h3\n3. e4-c4");
+        assert_eq!(replayed.unwrap().moves(), game.moves());
+    }
+
+    #[test]
+    fn invalid_transcript() {
+        // illegal move
+        assert!(Game::from_transcript("1. d11-d9 d9-d8").is_err());
+        // wrong move number
+        assert_eq!(
+            Game::from_transcript("2. d11-d9").unwrap_err(),
+            HnefataflError::InvalidNotation
+        );
+        assert_eq!(
+            Game::from_transcript("1. d11-d9 2. f8-c8").unwrap_err(),
+            HnefataflError::InvalidNotation
+        );
+        assert_eq!(
+            Game::from_transcript("1. d11-z9").unwrap_err(),
+            HnefataflError::InvalidNotation
+        );
+        // ambiguous move
+        assert_eq!(
+            Game::from_transcript("1. Ad9 Dc8 2. Ae4").unwrap_err(),
+            HnefataflError::InvalidNotation
+        );
+    }
+}
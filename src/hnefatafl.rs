@@ -1,6 +1,8 @@
-use std::cmp::Ordering;
 use std::error::Error;
 use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
 
 #[repr(u8)]
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -15,6 +17,14 @@ pub enum HnefataflError {
     TooManyCaptures,
     GameAlreadyWon,
     IllegalMove,
+    InvalidNotation,
+    OwnPieceAtTarget,
+    TooManyPieces,
+    PlyOutOfRange,
+    InvalidKingCount,
+    NothingToUndo,
+    InvalidBytes,
+    TileOccupied,
 }
 
 // {{{ impels for error
@@ -34,15 +44,106 @@ impl Display for HnefataflError {
             HnefataflError::TooManyCaptures => f.write_str("Too many captures"),
             HnefataflError::GameAlreadyWon => f.write_str("Game already won"),
             HnefataflError::IllegalMove => f.write_str("Illegal move"),
+            HnefataflError::InvalidNotation => f.write_str("Invalid notation"),
+            HnefataflError::OwnPieceAtTarget => {
+                f.write_str("Target of move is occupied by your own piece")
+            }
+            HnefataflError::TooManyPieces => f.write_str("Too many pieces on the board"),
+            HnefataflError::PlyOutOfRange => f.write_str("Ply is past the end of the game"),
+            HnefataflError::InvalidKingCount => f.write_str("Board must have exactly one king"),
+            HnefataflError::NothingToUndo => f.write_str("No move to undo"),
+            HnefataflError::InvalidBytes => f.write_str("Invalid board bytes"),
+            HnefataflError::TileOccupied => f.write_str("Tile already has a piece"),
         }
     }
 }
 
 impl Error for HnefataflError {}
 
+impl HnefataflError {
+    /// Stable numeric code of the error, used on the wire
+    ///
+    /// | Code | Error               |
+    /// |------|---------------------|
+    /// | 0    | `NoPieceToMove`     |
+    /// | 1    | `PieceInTheWay`     |
+    /// | 2    | `StartOutOfBounds`  |
+    /// | 3    | `TargetOutOfBounds` |
+    /// | 4    | `MoveNotHorVer`     |
+    /// | 5    | `WrongPieceColor`   |
+    /// | 6    | `IsProtectedTile`   |
+    /// | 7    | `TooManyCaptures`   |
+    /// | 8    | `GameAlreadyWon`    |
+    /// | 9    | `IllegalMove`       |
+    /// | 10   | `InvalidNotation`   |
+    /// | 11   | `OwnPieceAtTarget`  |
+    /// | 12   | `TooManyPieces`     |
+    /// | 13   | `PlyOutOfRange`     |
+    /// | 14   | `InvalidKingCount`  |
+    /// | 15   | `NothingToUndo`     |
+    /// | 16   | `InvalidBytes`      |
+    /// | 17   | `TileOccupied`      |
+    ///
+    /// These codes must never change, new errors get new codes.
+    pub fn to_code(&self) -> u8 {
+        match self {
+            HnefataflError::NoPieceToMove => 0,
+            HnefataflError::PieceInTheWay => 1,
+            HnefataflError::StartOutOfBounds => 2,
+            HnefataflError::TargetOutOfBounds => 3,
+            HnefataflError::MoveNotHorVer => 4,
+            HnefataflError::WrongPieceColor => 5,
+            HnefataflError::IsProtectedTile => 6,
+            HnefataflError::TooManyCaptures => 7,
+            HnefataflError::GameAlreadyWon => 8,
+            HnefataflError::IllegalMove => 9,
+            HnefataflError::InvalidNotation => 10,
+            HnefataflError::OwnPieceAtTarget => 11,
+            HnefataflError::TooManyPieces => 12,
+            HnefataflError::PlyOutOfRange => 13,
+            HnefataflError::InvalidKingCount => 14,
+            HnefataflError::NothingToUndo => 15,
+            HnefataflError::InvalidBytes => 16,
+            HnefataflError::TileOccupied => 17,
+        }
+    }
+}
+
+/// Decode an error from its code, see [`HnefataflError::to_code`]
+///
+/// Unknown codes are returned as the error.
+impl TryFrom<u8> for HnefataflError {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(HnefataflError::NoPieceToMove),
+            1 => Ok(HnefataflError::PieceInTheWay),
+            2 => Ok(HnefataflError::StartOutOfBounds),
+            3 => Ok(HnefataflError::TargetOutOfBounds),
+            4 => Ok(HnefataflError::MoveNotHorVer),
+            5 => Ok(HnefataflError::WrongPieceColor),
+            6 => Ok(HnefataflError::IsProtectedTile),
+            7 => Ok(HnefataflError::TooManyCaptures),
+            8 => Ok(HnefataflError::GameAlreadyWon),
+            9 => Ok(HnefataflError::IllegalMove),
+            10 => Ok(HnefataflError::InvalidNotation),
+            11 => Ok(HnefataflError::OwnPieceAtTarget),
+            12 => Ok(HnefataflError::TooManyPieces),
+            13 => Ok(HnefataflError::PlyOutOfRange),
+            14 => Ok(HnefataflError::InvalidKingCount),
+            15 => Ok(HnefataflError::NothingToUndo),
+            16 => Ok(HnefataflError::InvalidBytes),
+            17 => Ok(HnefataflError::TileOccupied),
+            _ => Err(value),
+        }
+    }
+}
+
 // }}}
 
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     Up = 0,
     Right = 1,
@@ -66,6 +167,30 @@ impl Direction {
             _ => 0,
         }
     }
+
+    /// The direction as one of the characters `U`, `R`, `D` and `L`
+    pub fn to_char(&self) -> char {
+        match self {
+            Self::Up => 'U',
+            Self::Right => 'R',
+            Self::Down => 'D',
+            Self::Left => 'L',
+        }
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = HnefataflError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'U' => Ok(Self::Up),
+            'R' => Ok(Self::Right),
+            'D' => Ok(Self::Down),
+            'L' => Ok(Self::Left),
+            _ => Err(HnefataflError::InvalidNotation),
+        }
+    }
 }
 
 impl From<u32> for Direction {
@@ -80,14 +205,60 @@ impl From<u32> for Direction {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Turn {
-    White,
-    Black,
+    White = 0,
+    Black = 1,
+}
+
+impl Turn {
+    /// Stable numeric value of the turn, `White` is 0 and `Black` is 1
+    pub fn to_u8(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Decode a turn from its numeric value, see [`Turn::to_u8`]
+///
+/// Unknown values are returned as the error.
+impl TryFrom<u8> for Turn {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Turn::White),
+            1 => Ok(Turn::Black),
+            _ => Err(value),
+        }
+    }
+}
+
+/// Writes `White` or `Black`
+impl Display for Turn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Turn::White => f.write_str("White"),
+            Turn::Black => f.write_str("Black"),
+        }
+    }
 }
 
-trait Color {
+/// Reads `White` or `Black`, or the first letter of either in upper or lower case
+impl FromStr for Turn {
+    type Err = HnefataflError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "White" | "W" | "w" => Ok(Turn::White),
+            "Black" | "B" | "b" => Ok(Turn::Black),
+            _ => Err(HnefataflError::InvalidNotation),
+        }
+    }
+}
+
+pub(crate) trait Color {
     fn color(&self) -> Turn;
     fn is_same_color<C: Color>(&self, other: &C) -> bool {
         self.color() == other.color()
@@ -107,7 +278,63 @@ impl Color for Turn {
     }
 }
 
+/// The state of a game
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameResult {
+    Ongoing,
+    /// The attackers (black) captured or encircled the king
+    AttackerWin,
+    /// The king (white) escaped to a corner or built an exit fort
+    DefenderWin,
+    /// The same position was reached three times
+    Draw,
+}
+
+impl GameResult {
+    /// The result when `turn` wins the game
+    pub fn win_for(turn: Turn) -> Self {
+        match turn {
+            Turn::White => GameResult::DefenderWin,
+            Turn::Black => GameResult::AttackerWin,
+        }
+    }
+
+    /// The side that won, or `None` if the game is ongoing or drawn
+    pub fn winner(&self) -> Option<Turn> {
+        match self {
+            GameResult::AttackerWin => Some(Turn::Black),
+            GameResult::DefenderWin => Some(Turn::White),
+            GameResult::Ongoing | GameResult::Draw => None,
+        }
+    }
+
+    /// Stable numeric code of the result, used on the wire
+    ///
+    /// `Ongoing` is 0, `AttackerWin` is 1, `DefenderWin` is 2 and `Draw` is 3
+    pub fn to_code(&self) -> u8 {
+        match self {
+            GameResult::Ongoing => 0,
+            GameResult::AttackerWin => 1,
+            GameResult::DefenderWin => 2,
+            GameResult::Draw => 3,
+        }
+    }
+
+    /// Get the result with the given code, see [`GameResult::to_code`]
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(GameResult::Ongoing),
+            1 => Some(GameResult::AttackerWin),
+            2 => Some(GameResult::DefenderWin),
+            3 => Some(GameResult::Draw),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Piece {
     King,
     Defender,
@@ -124,13 +351,62 @@ impl Color for Piece {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+/// The letter of the piece, `K`, `D` or `A`
+impl From<Piece> for char {
+    fn from(piece: Piece) -> Self {
+        match piece {
+            Piece::King => 'K',
+            Piece::Defender => 'D',
+            Piece::Attacker => 'A',
+        }
+    }
+}
+
+/// Reads the letter of a piece, see `From<Piece> for char`
+impl TryFrom<char> for Piece {
+    type Error = HnefataflError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'K' => Ok(Piece::King),
+            'D' => Ok(Piece::Defender),
+            'A' => Ok(Piece::Attacker),
+            _ => Err(HnefataflError::InvalidNotation),
+        }
+    }
+}
+
+/// Writes the letter of the piece, `K`, `D` or `A`
+impl Display for Piece {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", char::from(*self))
+    }
+}
+
+/// Reads the letter of a piece, see the `Display` implementation
+impl FromStr for Piece {
+    type Err = HnefataflError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Piece::try_from(c),
+            _ => Err(HnefataflError::InvalidNotation),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Move {
-    x: i32,
-    y: i32,
+    pub(crate) x: i32,
+    pub(crate) y: i32,
     direction: Direction,
     delta: i32,
+    /// Number of pieces captured, a shield wall captures several in one direction
     capture_count: i32,
+    /// Number of directions in `captures`
+    capture_directions: i32,
     captures: [Direction; 3],
     is_win: bool,
 }
@@ -147,12 +423,40 @@ impl Debug for CompactMove {
             .field("direction", &m.direction)
             .field("delta", &m.delta)
             .field("capture_count", &m.capture_count)
+            .field("capture_directions", &m.capture_directions)
             .field("captures", &m.captures)
             .field("is_win", &m.is_win)
             .finish()
     }
 }
 
+impl CompactMove {
+    /// The packed representation of the move
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+
+    /// Create a move from a packed representation, as given by [`CompactMove::as_u32`]
+    pub fn from_u32(value: u32) -> Self {
+        Self(value)
+    }
+
+    /// Check if the move won the game, without unpacking the move
+    pub fn is_win(&self) -> bool {
+        0b0001 & (self.0 >> 22) == 1
+    }
+
+    /// The number of pieces captured by the move, without unpacking the move
+    pub fn capture_count(&self) -> u32 {
+        0b1111 & (self.0 >> 23)
+    }
+
+    /// The direction of the move, without unpacking the move
+    pub fn direction(&self) -> Direction {
+        (0b0011 & (self.0 >> 8)).into()
+    }
+}
+
 impl From<[u8; 4]> for CompactMove {
     fn from(bytes: [u8; 4]) -> Self {
         Self(u32::from_le_bytes(bytes))
@@ -182,8 +486,9 @@ impl From<Move> for CompactMove {
         representation |= (0b0011 & (m.captures[0] as u32)) << 14;
         representation |= (0b0011 & (m.captures[1] as u32)) << 16;
         representation |= (0b0011 & (m.captures[2] as u32)) << 18;
-        representation |= (0b0011 & (m.capture_count as u32)) << 20;
+        representation |= (0b0011 & (m.capture_directions as u32)) << 20;
         representation |= (0b0001 & (m.is_win as u32)) << 22;
+        representation |= (0b1111 & (m.capture_count as u32)) << 23;
 
         Self(representation)
     }
@@ -200,8 +505,9 @@ impl From<CompactMove> for Move {
         let capture1 = 0b0011 & (representation >> 14);
         let capture2 = 0b0011 & (representation >> 16);
         let capture3 = 0b0011 & (representation >> 18);
-        let capture_count = 0b0011 & (representation >> 20);
+        let capture_directions = 0b0011 & (representation >> 20);
         let is_win = 0b0001 & (representation >> 22);
+        let capture_count = 0b1111 & (representation >> 23);
 
         Self {
             x: x as i32,
@@ -209,6 +515,7 @@ impl From<CompactMove> for Move {
             direction: direction.into(),
             delta: delta as i32,
             capture_count: capture_count as i32,
+            capture_directions: capture_directions as i32,
             captures: [capture1.into(), capture2.into(), capture3.into()],
             is_win: is_win == 1,
         }
@@ -238,13 +545,98 @@ impl Move {
             direction,
             delta,
             capture_count: 0,
+            capture_directions: 0,
             captures: [Direction::Up, Direction::Up, Direction::Up],
             is_win: false,
         })
     }
 
-    pub fn compact(self) -> CompactMove {
-        self.into()
+    /// How promising the move looks for search, higher is better
+    fn priority(&self) -> i32 {
+        if self.is_win {
+            4
+        } else {
+            self.capture_count
+        }
+    }
+
+    /// Check if two moves move from the same tile to the same tile
+    ///
+    /// Unlike `==` this ignores captures and whether the move wins
+    pub fn same_path(&self, other: &Move) -> bool {
+        (self.x, self.y, self.direction, self.delta)
+            == (other.x, other.y, other.direction, other.delta)
+    }
+
+    /// The tile the piece moves from
+    ///
+    /// Named `origin` since `Move::from` is the constructor
+    pub fn origin(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    /// The tile the piece ends up on
+    pub fn to(&self) -> (i32, i32) {
+        self.destination()
+    }
+
+    /// The tile the piece ends up on
+    pub fn destination(&self) -> (i32, i32) {
+        (
+            self.x + self.delta * self.direction.x(),
+            self.y + self.delta * self.direction.y(),
+        )
+    }
+
+    /// Check if both the start and the destination are on a board with the given size
+    pub fn is_in_bounds(&self, size: i32) -> bool {
+        let (to_x, to_y) = self.destination();
+        [self.x, self.y, to_x, to_y]
+            .iter()
+            .all(|c| (0..size).contains(c))
+    }
+
+    pub fn compact(&self) -> CompactMove {
+        (*self).into()
+    }
+
+    /// Write the move in algebraic notation on the standard board, like `a4-d4`
+    ///
+    /// Files are the letters `a` to `k` from the left, ranks are the numbers 1 to 11 from the
+    /// bottom. Moves that capture have `x` instead of `-`, and winning moves end with `#`,
+    /// like `e5xe1#`.
+    pub fn to_algebraic(&self) -> String {
+        let (to_x, to_y) = self.destination();
+        format!(
+            "{}{}{}{}{}{}",
+            file_name(self.x),
+            rank_name(self.y, 11),
+            if self.capture_count > 0 { 'x' } else { '-' },
+            file_name(to_x),
+            rank_name(to_y, 11),
+            if self.is_win { "#" } else { "" }
+        )
+    }
+
+    /// Read a move written by [`Move::to_algebraic`]
+    ///
+    /// Which pieces are captured depends on the board, so `x` is accepted but the move gets no
+    /// captures. A trailing `#` marks the move as winning.
+    pub fn from_algebraic(s: &str) -> Result<Self, HnefataflError> {
+        let (s, is_win) = match s.strip_suffix('#') {
+            Some(s) => (s, true),
+            None => (s, false),
+        };
+        let (from, to) = s
+            .split_once(['-', 'x'])
+            .ok_or(HnefataflError::InvalidNotation)?;
+
+        let (x, y) = parse_tile(from, 11)?;
+        let (to_x, to_y) = parse_tile(to, 11)?;
+
+        let mut m = Move::from(x, y, to_x, to_y)?;
+        m.is_win = is_win;
+        Ok(m)
     }
 
     pub fn set_win(&mut self) {
@@ -252,9 +644,19 @@ impl Move {
     }
 
     pub fn add_capture(&mut self, capture_direction: Direction) -> Result<(), HnefataflError> {
-        if self.capture_count < 3 {
-            self.captures[self.capture_count as usize] = capture_direction;
-            self.capture_count += 1;
+        self.add_captures(capture_direction, 1)
+    }
+
+    /// Add a capture of `pieces` pieces in one direction, as with a shield wall
+    pub(crate) fn add_captures(
+        &mut self,
+        capture_direction: Direction,
+        pieces: i32,
+    ) -> Result<(), HnefataflError> {
+        if self.capture_directions < 3 {
+            self.captures[self.capture_directions as usize] = capture_direction;
+            self.capture_directions += 1;
+            self.capture_count += pieces;
         } else {
             Err(HnefataflError::TooManyCaptures)?
         }
@@ -262,14 +664,227 @@ impl Move {
     }
 }
 
-#[derive(PartialEq, Clone)]
-pub struct Board {
-    board: [[Option<Piece>; 11]; 11],
+/// Rules that differ between variants of the game
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RuleSet {
+    /// Empty corners act as an enemy when capturing soldiers
+    pub corners_hostile: bool,
+    /// The center tile is a throne, which only the king may occupy.
+    /// Without a throne the center is an ordinary tile.
+    pub has_throne: bool,
+    /// A piece next to the throne can not be captured against the king sitting on it
+    pub throne_immunity: bool,
+    /// Most attackers there can be on the board, the number at the start of the game
+    pub max_attackers: usize,
+    /// Most defenders (not counting the king) there can be on the board
+    pub max_defenders: usize,
+    /// The king is captured along with a shield wall it is part of, instead of surviving it
+    pub shield_wall_captures_king: bool,
+    /// The king takes part in captures, as the moving piece or the one on the other side
+    pub armed_king: bool,
+    /// The empty throne is hostile to the king, so the king next to it is captured by three
+    /// attackers
+    pub hostile_throne: bool,
+    /// The edge of the board is hostile to the king, so the king on the edge is captured by three
+    /// attackers, and next to a hostile corner by two
+    pub edge_king_capture: bool,
+    /// Encircling the king only wins when every defender is inside the ring as well, so no
+    /// defender is left to break it from the outside
+    pub encircle_defenders: bool,
+}
+
+impl RuleSet {
+    /// The default rules, with the number of pieces in the starting position of the variant
+    /// played on a board of `size` by `size` tiles
+    ///
+    /// These are Brandub on 7 by 7, Tablut on 9 by 9 and standard hnefatafl on 11 by 11.
+    /// Smaller boards have no starting position, and may be filled with pieces.
+    pub fn for_size(size: usize) -> Self {
+        let (max_attackers, max_defenders) = match size {
+            7 => (8, 4),
+            9 => (16, 8),
+            11 => (24, 12),
+            _ => (size * size, size * size),
+        };
+
+        Self {
+            max_attackers,
+            max_defenders,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for RuleSet {
+    /// The rules of standard hnefatafl on 11 by 11 tiles
+    fn default() -> Self {
+        Self {
+            corners_hostile: true,
+            has_throne: true,
+            throne_immunity: false,
+            max_attackers: 24,
+            max_defenders: 12,
+            shield_wall_captures_king: false,
+            armed_king: true,
+            hostile_throne: true,
+            edge_king_capture: false,
+            encircle_defenders: false,
+        }
+    }
+}
+
+/// The coordinates of a tile, checked to be on a board of `N` by `N` tiles
+///
+/// Boards can only be indexed by positions of their own size, see [`Board::position`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Position<const N: usize = 11> {
+    x: u8,
+    y: u8,
+}
+
+impl<const N: usize> Position<N> {
+    /// Create a position, or `None` if a coordinate is outside `0..N`
+    pub fn new(x: i32, y: i32) -> Option<Self> {
+        if (0..N as i32).contains(&x) && (0..N as i32).contains(&y) {
+            Some(Self {
+                x: x as u8,
+                y: y as u8,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn x(&self) -> i32 {
+        self.x as i32
+    }
+
+    pub fn y(&self) -> i32 {
+        self.y as i32
+    }
+}
+
+impl<const N: usize> From<Position<N>> for (i32, i32) {
+    fn from(position: Position<N>) -> Self {
+        (position.x(), position.y())
+    }
+}
+
+/// Check the coordinates, see [`Position::new`]
+///
+/// Coordinates out of range are returned as the error.
+impl<const N: usize> TryFrom<(i32, i32)> for Position<N> {
+    type Error = (i32, i32);
+
+    fn try_from((x, y): (i32, i32)) -> Result<Self, Self::Error> {
+        Self::new(x, y).ok_or((x, y))
+    }
+}
+
+// {{{ Zobrist hashing
+
+/// Step the splitmix64 generator, returning the new state and a random number
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (state, z ^ (z >> 31))
+}
+
+/// Random numbers for the Zobrist hash, one for each piece on each tile
+const ZOBRIST_PIECES: [[u64; 3]; 121] = {
+    let mut table = [[0; 3]; 121];
+    let mut state = 0x6866_7461_666c_u64;
+
+    let mut i = 0;
+    while i < 121 {
+        let mut j = 0;
+        while j < 3 {
+            let (next, value) = splitmix64(state);
+            state = next;
+            table[i][j] = value;
+            j += 1;
+        }
+        i += 1;
+    }
+
+    table
+};
+
+/// Random number for the Zobrist hash, added when black is to move
+const ZOBRIST_BLACK: u64 = splitmix64(0x0062_6c61_636b).1;
+
+// }}}
+
+/// A board of `N` by `N` tiles
+///
+/// `N` must be odd, so there is a center tile for the throne, and at most 11.
+#[derive(Clone)]
+pub struct Board<const N: usize = 11> {
+    board: [[Option<Piece>; N]; N],
+    turn: Turn,
+    result: GameResult,
+    rules: RuleSet,
+    /// Number of moves performed
+    ply: u32,
+    /// Number of pieces captured from each side
+    white_captured: u32,
+    black_captured: u32,
+    /// Hashes of the positions reached, to detect repetition
+    positions: Vec<u64>,
+    /// The moves performed, to be able to undo them
+    history: Vec<HistoryEntry>,
+    /// Zobrist hash of the pieces and the side to move, kept up to date as they change
+    zobrist: u64,
+    /// A tile handed out by `IndexMut`, whose piece is left out of `zobrist` until the next change
+    unhashed: Option<(usize, usize)>,
+}
+
+/// What is needed to take back a move, see [`Board::undo`]
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+    piece: Piece,
+    from: (i32, i32),
+    to: (i32, i32),
+    captured: Vec<(i32, i32, Piece)>,
+    turn: Turn,
+    result: GameResult,
+    positions: usize,
+}
+
+/// Statistics about a game in progress, see [`Board::stats`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GameStats {
+    /// Number of moves performed
+    pub ply: u32,
+    /// Number of moves available to the side to move
+    pub legal_moves: usize,
+    /// Number of white pieces (defenders and king) captured
+    pub white_captured: u32,
+    /// Number of black pieces (attackers) captured
+    pub black_captured: u32,
+}
+
+/// A saved state of a board, see [`Board::checkpoint`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    tiles: [u8; 31],
     turn: Turn,
-    is_won: bool,
+    result: GameResult,
+    ply: u32,
+    white_captured: u32,
+    black_captured: u32,
+    positions: usize,
+    history: usize,
+    /// To tell if the board is still in the game the checkpoint was taken from
+    zobrist: u64,
 }
 
-impl Debug for Board {
+/// The 11 by 11 board of standard hnefatafl
+pub type StandardBoard = Board<11>;
+
+impl<const N: usize> Debug for Board<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Board")
             .field("board", &self.board)
@@ -278,7 +893,30 @@ impl Debug for Board {
     }
 }
 
-impl Board {
+/// Boards are equal if they have the same pieces, turn, result and rules.
+/// The counters of how the game got there are not compared.
+impl<const N: usize> PartialEq for Board<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.board == other.board
+            && self.turn == other.turn
+            && self.result == other.result
+            && self.rules == other.rules
+    }
+}
+
+/// Hashes the [`Board::zobrist`] hash, so boards that are equal hash the same
+impl<const N: usize> Hash for Board<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.zobrist().hash(state);
+    }
+}
+
+impl Board<11> {
+    /// Create an empty board
+    pub fn empty() -> Self {
+        Self::blank()
+    }
+
     /// Create a new board with the pieces in their starting positions
     pub fn new() -> Self {
         let mut board = Self::empty();
@@ -308,613 +946,4318 @@ impl Board {
         board
     }
 
-    /// Create an empty board
-    pub fn empty() -> Self {
-        Self {
-            board: [[None; 11]; 11],
-            turn: Turn::Black,
-            is_won: false,
-        }
+    /// Create a new board with the pieces in their starting positions, where `turn` moves first
+    pub fn new_with_first_turn(turn: Turn) -> Self {
+        let mut board = Self::new();
+        board.set_turn(turn);
+        board
     }
 
-    /// Set the turn
-    pub fn set_turn(&mut self, turn: Turn) {
-        self.turn = turn;
+    /// Create a new board, with room in its history for `n` moves
+    pub fn with_capacity_history(n: usize) -> Self {
+        let mut board = Self::new();
+        board.reserve_history(n);
+        board
     }
 
-    pub fn get_turn(&self) -> Turn {
-        self.turn
+    /// Create the board of a game after its first `n` moves, starting from the starting position
+    pub fn replay_to_ply(moves: &[CompactMove], n: usize) -> Result<Self, HnefataflError> {
+        if n > moves.len() {
+            return Err(HnefataflError::PlyOutOfRange);
+        }
+
+        let mut board = Self::new();
+        for m in &moves[..n] {
+            board.do_move(&(*m).into())?;
+        }
+
+        Ok(board)
     }
 
-    pub fn set_won(&mut self, won: bool) {
-        self.is_won = won;
+    /// Encode the board as input planes for machine learning
+    ///
+    /// The planes are the king, the defenders, the attackers and the turn, in that order.
+    /// Tiles are indexed row by row, `y * 11 + x`, and the turn plane is filled with
+    /// [`Turn::to_u8`] of the side to move.
+    pub fn to_planes(&self) -> [[u8; 121]; 4] {
+        let mut planes = [[0; 121]; 4];
+
+        for (x, y, piece) in self.pieces() {
+            let plane = match piece {
+                Piece::King => 0,
+                Piece::Defender => 1,
+                Piece::Attacker => 2,
+            };
+            planes[plane][(y * 11 + x) as usize] = 1;
+        }
+        planes[3] = [self.turn.to_u8(); 121];
+
+        planes
     }
 
-    pub fn is_game_over(&self) -> bool {
-        self.is_won
+    /// Create a board from planes made by [`Board::to_planes`]
+    ///
+    /// Any non-zero value counts as a set bit. The king plane must have exactly one bit set, and
+    /// no tile may be set in more than one of the piece planes.
+    pub fn from_planes(planes: &[[u8; 121]; 4]) -> Result<Self, HnefataflError> {
+        if planes[0].iter().filter(|&&bit| bit != 0).count() != 1 {
+            return Err(HnefataflError::InvalidKingCount);
+        }
+
+        let mut board = Self::blank();
+        for (plane, piece) in planes
+            .iter()
+            .zip([Piece::King, Piece::Defender, Piece::Attacker])
+        {
+            for (i, &bit) in plane.iter().enumerate() {
+                let (x, y) = (i as i32 % 11, i as i32 / 11);
+                if bit != 0 {
+                    if board.get_piece_unchecked(x, y).is_some() {
+                        return Err(HnefataflError::TileOccupied);
+                    }
+                    board.place_piece(piece, x, y);
+                }
+            }
+        }
+        board.set_turn(if planes[3][0] != 0 {
+            Turn::Black
+        } else {
+            Turn::White
+        });
+
+        Ok(board)
     }
+}
 
-    /// Get a piece, but do not check if the coordinates are within bounds
-    pub fn get_piece_unchecked(&self, x: i32, y: i32) -> Option<Piece> {
-        self.board[y as usize][x as usize]
+impl Board<7> {
+    /// Create a Brandub board with the pieces in their starting positions
+    ///
+    /// The king stands on the throne with a defender on each side, and two attackers stand in
+    /// the middle of each edge.
+    pub fn brandub() -> Self {
+        let mut board = Self::blank();
+
+        board.place_piece(Piece::King, 3, 3);
+        for (x, y) in [(3, 2), (3, 4), (2, 3), (4, 3)] {
+            board.place_piece(Piece::Defender, x, y);
+        }
+        for (x, y) in [
+            (3, 0),
+            (3, 1),
+            (3, 5),
+            (3, 6),
+            (0, 3),
+            (1, 3),
+            (5, 3),
+            (6, 3),
+        ] {
+            board.place_piece(Piece::Attacker, x, y);
+        }
+
+        board
     }
+}
 
-    /// Get a piece, but check if the coordinates are within bounds
-    /// returns None if the coordinates are out of bounds
-    pub fn get_piece_checked(&self, x: i32, y: i32) -> Option<Piece> {
-        if !(0..=10).contains(&x) || !(0..=10).contains(&y) {
-            return None;
+impl Board<9> {
+    /// Create a Tablut board with the pieces in their starting positions
+    ///
+    /// The king stands on the throne with two defenders on each side, and four attackers stand
+    /// in the middle of each edge.
+    pub fn tablut() -> Self {
+        let mut board = Self::blank();
+
+        board.place_piece(Piece::King, 4, 4);
+        for i in [2, 3, 5, 6] {
+            board.place_piece(Piece::Defender, 4, i);
+            board.place_piece(Piece::Defender, i, 4);
         }
 
-        self.board[y as usize][x as usize]
+        for i in 3..=5 {
+            board.place_piece(Piece::Attacker, i, 0);
+            board.place_piece(Piece::Attacker, i, 8);
+            board.place_piece(Piece::Attacker, 0, i);
+            board.place_piece(Piece::Attacker, 8, i);
+        }
+        board.place_piece(Piece::Attacker, 4, 1);
+        board.place_piece(Piece::Attacker, 4, 7);
+        board.place_piece(Piece::Attacker, 1, 4);
+        board.place_piece(Piece::Attacker, 7, 4);
+
+        board
     }
+}
 
-    /// Place a piece on the board
-    fn place(&mut self, piece: Option<Piece>, x: i32, y: i32) {
-        self.board[y as usize][x as usize] = piece;
+impl<const N: usize> Board<N> {
+    /// Index of the last row and column
+    const LAST: i32 = N as i32 - 1;
+    /// Index of the center row and column, where the throne is
+    const CENTER: i32 = N as i32 / 2;
+    /// Boards must fit in a [`Checkpoint`], and have a center tile
+    const SIZE_OK: () = assert!(
+        N % 2 == 1 && N <= 11,
+        "board size must be odd and at most 11"
+    );
+
+    /// Create an empty board of any size, with the rules for that size
+    pub fn blank() -> Self {
+        let () = Self::SIZE_OK;
+
+        Self {
+            board: [[None; N]; N],
+            turn: Turn::Black,
+            result: GameResult::Ongoing,
+            rules: RuleSet::for_size(N),
+            ply: 0,
+            white_captured: 0,
+            black_captured: 0,
+            positions: Vec::new(),
+            history: Vec::new(),
+            zobrist: ZOBRIST_BLACK,
+            unhashed: None,
+        }
     }
 
-    /// place a piece on the board, but do not check if the coordinates are within bounds
-    fn place_piece(&mut self, piece: Piece, x: i32, y: i32) {
-        self.place(Some(piece), x, y);
+    /// A position on this size of board, or `None` if a coordinate is outside it
+    pub fn position(x: i32, y: i32) -> Option<Position<N>> {
+        Position::new(x, y)
     }
 
-    /// Remove a piece from the board
-    fn remove_piece(&mut self, x: i32, y: i32) {
-        self.place(None, x, y);
+    /// Make room in the history for at least `additional` more moves
+    ///
+    /// Saves reallocating the history during long games
+    pub fn reserve_history(&mut self, additional: usize) {
+        self.history.reserve(additional);
+        self.positions.reserve(additional);
     }
 
-    /// Move a piece without checking if the move is valid
-    pub fn move_piece_uncheced(&mut self, x: i32, y: i32, new_x: i32, new_y: i32) {
-        self.place(self.get_piece_unchecked(x, y), new_x, new_y);
-        self.remove_piece(x, y);
+    /// Set the turn
+    pub fn set_turn(&mut self, turn: Turn) {
+        if turn != self.turn {
+            self.zobrist ^= ZOBRIST_BLACK;
+        }
+        self.turn = turn;
     }
 
-    /// Move a piece, checking if the move is valid
-    pub fn move_piece(
-        &mut self,
-        x: i32,
-        y: i32,
-        new_x: i32,
-        new_y: i32,
-    ) -> Result<CompactMove, HnefataflError> {
-        // Important to check if the bounds are met before trying to access the piece
-        if !(0..=10).contains(&x) || !(0..=10).contains(&y) {
-            return Err(HnefataflError::StartOutOfBounds);
-        }
-        if !(0..=10).contains(&new_x) || !(0..=10).contains(&new_y) {
-            return Err(HnefataflError::TargetOutOfBounds);
-        }
-        // Check if bad direction (gotta be rook move)
-        if x != new_x && y != new_y {
-            return Err(HnefataflError::MoveNotHorVer);
-        }
-        if self.is_won {
+    pub fn get_turn(&self) -> Turn {
+        self.turn
+    }
+
+    /// Pass the turn to the opponent without moving a piece
+    ///
+    /// Meant for analysis, this is not a legal move in the game
+    pub fn pass(&mut self) -> Result<(), HnefataflError> {
+        if self.is_game_over() {
             return Err(HnefataflError::GameAlreadyWon);
         }
 
-        let piece = self
-            .get_piece_unchecked(x, y)
-            .ok_or(HnefataflError::NoPieceToMove)?;
+        self.set_turn(self.turn.opposite());
+        Ok(())
+    }
 
-        if !self.turn.is_same_color(&piece) {
-            return Err(HnefataflError::WrongPieceColor);
-        }
+    /// Set the rules the game is played by
+    pub fn set_rules(&mut self, rules: RuleSet) {
+        self.rules = rules;
+    }
 
-        if piece != Piece::King && self.is_fortress(new_x, new_y) {
-            return Err(HnefataflError::IsProtectedTile);
-        }
-        use Ordering::*;
+    pub fn get_rules(&self) -> &RuleSet {
+        &self.rules
+    }
 
-        let (start_x, end_x, start_y, end_y) = match (x.cmp(&new_x), y.cmp(&new_y)) {
-            (Less, Equal) => (x + 1, new_x, y, y),
-            (Greater, Equal) => (new_x, x - 1, y, y),
-            (Equal, Less) => (x, x, y + 1, new_y),
-            (Equal, Greater) => (x, x, new_y, y - 1),
-            (_a, _b) => Err(HnefataflError::IllegalMove)?,
-        };
+    /// Set the result of the game
+    pub fn set_result(&mut self, result: GameResult) {
+        self.result = result;
+    }
 
-        for i in start_x..=end_x {
-            for j in start_y..=end_y {
-                if self.get_piece_unchecked(i, j).is_some() {
-                    return Err(HnefataflError::PieceInTheWay);
-                }
-            }
-        }
+    pub fn is_game_over(&self) -> bool {
+        self.result != GameResult::Ongoing
+    }
 
-        self.remove_piece(x, y);
-        self.place_piece(piece, new_x, new_y);
+    /// Check if one of the sides has won, a draw does not count
+    pub fn is_won(&self) -> bool {
+        self.winner().is_some()
+    }
 
-        let mut mv = Move::from(x, y, new_x, new_y)?;
+    /// The side that won the game, white when the king escapes and black when it is taken
+    pub fn winner(&self) -> Option<Turn> {
+        self.result.winner()
+    }
 
-        // try capture in all directions
-        let mut capture = |x, y, dir| {
-            if let Some(p) = self.try_capture(x, y, dir) {
-                if p == Piece::King {
-                    mv.set_win();
-                }
-                mv.add_capture(dir).unwrap();
-            }
-        };
-        capture(new_x, new_y + 1, Direction::Up);
-        capture(new_x, new_y - 1, Direction::Down);
-        capture(new_x + 1, new_y, Direction::Right);
-        capture(new_x - 1, new_y, Direction::Left);
+    /// Get the result of the game so far
+    pub fn result(&self) -> GameResult {
+        self.result
+    }
 
-        // TODO: Check for win conditions (king in the corner, king captured)
-        if piece == Piece::King && self.is_fortress(new_x, new_y) {
-            mv.set_win();
+    /// Check if the current position, with the same side to move, has been reached three times
+    pub fn is_draw_by_repetition(&self) -> bool {
+        let current = self.zobrist();
+        self.positions
+            .iter()
+            .filter(|&&hash| hash == current)
+            .count()
+            >= 3
+    }
+
+    /// Zobrist hash of the pieces on the board and the side to move
+    ///
+    /// The hash is the same from run to run, and is updated as pieces move instead of being
+    /// computed over the whole board, so it is cheap to use for transposition tables.
+    pub fn zobrist(&self) -> u64 {
+        match self.unhashed {
+            Some((x, y)) => match self.board[y][x] {
+                Some(piece) => self.zobrist ^ Self::zobrist_key(piece, x as i32, y as i32),
+                None => self.zobrist,
+            },
+            None => self.zobrist,
         }
+    }
 
-        if !mv.is_win {
-            self.turn = self.turn.opposite();
-        } else {
-            self.is_won = true;
+    /// The Zobrist hash of the pieces only, leaving out the side to move
+    ///
+    /// For transposition tables that keep the side to move apart from the hash
+    pub fn hash_without_turn(&self) -> u64 {
+        match self.turn {
+            Turn::Black => self.zobrist() ^ ZOBRIST_BLACK,
+            Turn::White => self.zobrist(),
         }
+    }
 
-        Ok(mv.into())
+    /// Describe how the game ended, for showing to players
+    ///
+    /// Returns nothing while the game is in progress
+    pub fn describe_outcome(&self) -> Option<String> {
+        // a move is one ply by each side
+        let move_number = self.ply.div_ceil(2);
+
+        match self.result() {
+            GameResult::Ongoing => None,
+            GameResult::Draw => Some(format!(
+                "Draw: position repeated three times on move {}",
+                move_number
+            )),
+            GameResult::AttackerWin
+                if self.king_position().is_some() && self.king_is_encircled() =>
+            {
+                Some(format!(
+                    "Black wins: king encircled on move {}",
+                    move_number
+                ))
+            }
+            GameResult::AttackerWin
+                if self.king_position().is_some() && self.is_blocked(Turn::White) =>
+            {
+                Some(format!(
+                    "Black wins: white has no moves on move {}",
+                    move_number
+                ))
+            }
+            GameResult::AttackerWin => {
+                Some(format!("Black wins: king captured on move {}", move_number))
+            }
+            GameResult::DefenderWin => {
+                let place = match self.king_position() {
+                    Some((x, y)) if self.is_corner(x, y) => match (x == 0, y == 0) {
+                        (true, true) => "the northwest corner",
+                        (false, true) => "the northeast corner",
+                        (true, false) => "the southwest corner",
+                        (false, false) => "the southeast corner",
+                    },
+                    _ if self.is_blocked(Turn::Black) => {
+                        return Some(format!(
+                            "White wins: black has no moves on move {}",
+                            move_number
+                        ))
+                    }
+                    _ => "a fortress",
+                };
+                Some(format!(
+                    "White wins: king escaped to {} on move {}",
+                    place, move_number
+                ))
+            }
+        }
     }
 
-    pub fn do_move(&mut self, m: &Move) -> Result<CompactMove, HnefataflError> {
-        self.move_piece(
-            m.x,
-            m.y,
-            m.x + m.delta * m.direction.x(),
-            m.y + m.delta * m.direction.y(),
-        )
+    /// Check if the king is on the throne in the center of the board
+    pub fn is_king_on_throne(&self) -> bool {
+        self.is_throne(Self::CENTER, Self::CENTER)
+            && self.get_piece_unchecked(Self::CENTER, Self::CENTER) == Some(Piece::King)
     }
 
-    /// Check if the tile is a fortress tile.
-    ///
-    /// The fortress tiles are (0,0), (0,10), (10,0), (10,10) and (5,5).
-    /// Only the king may occupy a fortress.
+    /// Check that the board has exactly one king
     ///
-    /// The arguments are not checked if they are within bounds
-    fn is_fortress(&self, x: i32, y: i32) -> bool {
-        matches!((x, y), (0, 0) | (0, 10) | (10, 0) | (10, 10) | (5, 5))
+    /// Much of the game logic assumes there is one king, so boards built from outside data
+    /// should be checked with this.
+    pub fn is_valid(&self) -> bool {
+        self.pieces().filter(|(_, _, p)| *p == Piece::King).count() == 1
     }
 
-    /// Checks if the specified tile is an enemy tile
+    /// Check that there are not more pieces on the board than the rules allow
     ///
-    /// Returns false if the tile is out of bounds
-    fn is_enemy(&self, start_piece: &Piece, x: i32, y: i32) -> bool {
-        if !(0..10).contains(&x) || !(0..10).contains(&y) {
-            return false;
+    /// Fewer pieces are fine, since pieces are captured during the game
+    pub fn validate_counts(&self, rules: &RuleSet) -> Result<(), HnefataflError> {
+        if self.attackers().count() > rules.max_attackers
+            || self.defenders().count() > rules.max_defenders
+            || self.kings().count() > 1
+        {
+            return Err(HnefataflError::TooManyPieces);
         }
 
-        let check_square = self.get_piece_unchecked(x, y);
+        Ok(())
+    }
 
-        // if the king occupies a fortress, then the position is not an enemy to the white pieces
-        // This choice could possibly be changed
-        if let Some(piece) = check_square {
-            !start_piece.is_same_color(&piece)
-        } else {
-            // if the square is empty, but is a fortress, then it is an enemy to all pieces
-            // if it is an empty, ordinary tile, then it is not an enemy
-            self.is_fortress(x, y)
+    /// Save the state of the board, so it can be restored later
+    ///
+    /// The tiles are packed with two bits per tile, so this is cheaper to keep around than a clone
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            tiles: self.pack_tiles(),
+            turn: self.turn,
+            result: self.result,
+            ply: self.ply,
+            white_captured: self.white_captured,
+            black_captured: self.black_captured,
+            positions: self.positions.len(),
+            history: self.history.len(),
+            zobrist: self.zobrist(),
         }
     }
 
-    /// Try to capture a piece.
-    /// If this piece is captured, then return the piece.
-    fn try_capture(&mut self, x: i32, y: i32, direction: Direction) -> Option<Piece> {
-        let p = self.get_piece_checked(x, y)?;
-
-        use Direction::*;
+    /// Restore the board to a saved state
+    ///
+    /// The rules of the board are kept as they are.
+    /// Positions reached after the checkpoint are forgotten when checking for repetition, and
+    /// moves performed after it can not be undone. When the checkpoint is not from earlier in
+    /// the moves of this board, such as one from later in the game after an undo, the history is
+    /// cleared instead, so no moves can be undone.
+    pub fn restore(&mut self, checkpoint: &Checkpoint) {
+        let same_game = self.history.len() >= checkpoint.history
+            && self.positions.len() >= checkpoint.positions
+            && checkpoint
+                .positions
+                .checked_sub(1)
+                .is_none_or(|last| self.positions[last] == checkpoint.zobrist);
+
+        if same_game {
+            self.positions.truncate(checkpoint.positions);
+            self.history.truncate(checkpoint.history);
+        } else {
+            self.positions.clear();
+            self.history.clear();
+        }
+        self.unpack_tiles(&checkpoint.tiles);
+        self.set_turn(checkpoint.turn);
+        self.result = checkpoint.result;
+        self.ply = checkpoint.ply;
+        self.white_captured = checkpoint.white_captured;
+        self.black_captured = checkpoint.black_captured;
+    }
 
-        let (lx, ly) = match direction {
-            Up | Down => (x, y - 1),
-            Left | Right => (x - 1, y),
-        };
+    /// Pack the tiles with two bits each, row by row
+    fn pack_tiles(&self) -> [u8; 31] {
+        let mut packed = [0; 31];
+
+        for (i, piece) in self.board.iter().flatten().enumerate() {
+            let bits = match piece {
+                None => 0,
+                Some(Piece::Attacker) => 1,
+                Some(Piece::Defender) => 2,
+                Some(Piece::King) => 3,
+            };
+            packed[i / 4] |= bits << (2 * (i % 4));
+        }
 
-        let (rx, ry) = match direction {
-            Up | Down => (x, y + 1),
-            Left | Right => (x + 1, y),
-        };
+        packed
+    }
 
-        // checking for normal capture
-        if p != Piece::King && self.is_enemy(&p, lx, ly) && self.is_enemy(&p, rx, ry) {
-            self.remove_piece(x, y);
-            return Some(p);
+    /// Unpack tiles packed by [`Board::pack_tiles`]
+    fn unpack_tiles(&mut self, packed: &[u8; 31]) {
+        for i in 0..N * N {
+            let piece = match (packed[i / 4] >> (2 * (i % 4))) & 0b11 {
+                0 => None,
+                1 => Some(Piece::Attacker),
+                2 => Some(Piece::Defender),
+                _ => Some(Piece::King),
+            };
+            self.place(piece, (i % N) as i32, (i / N) as i32);
         }
+    }
 
-        // King capture
-        if p == Piece::King
-            && self.is_enemy(&p, x + 1, y)
-            && self.is_enemy(&p, x - 1, y)
-            && self.is_enemy(&p, x, y + 1)
-            && self.is_enemy(&p, x, y - 1)
-        {
-            self.remove_piece(x, y);
-            return Some(p);
-        }
+    /// Number of bytes used by the packed tiles in [`Board::to_bytes`]
+    const PACKED_LEN: usize = (N * N).div_ceil(4);
 
-        None
+    /// Serialize the board to bytes
+    ///
+    /// The tiles are packed with two bits each, row by row, followed by one byte for the turn,
+    /// see [`Turn::to_u8`], and one byte for the result, see [`GameResult::to_code`].
+    /// The rules, the capture counts and the move history are not included.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.pack_tiles()[..Self::PACKED_LEN].to_vec();
+        bytes.push(self.turn.to_u8());
+        bytes.push(self.result.to_code());
+        bytes
     }
 
-    /// Returns a list of all target tiles available from the specified tile
-    /// This does check whose turn it is
-    fn moves_from(&self, x: i32, y: i32) -> Vec<(i32, i32)> {
-        if !(0..=10).contains(&x) || !(0..=10).contains(&y) {
-            return Vec::new();
+    /// Create a board from bytes made by [`Board::to_bytes`]
+    ///
+    /// The board gets the default rules.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, HnefataflError> {
+        if bytes.len() != Self::PACKED_LEN + 2 {
+            return Err(HnefataflError::InvalidBytes);
         }
 
-        // We have already checked the bounds, no need to check again
-        let p = self.get_piece_unchecked(x, y);
-        if p.is_none() || !self.turn.is_same_color(&p.unwrap()) {
-            return Vec::new();
+        let mut packed = [0; 31];
+        packed[..Self::PACKED_LEN].copy_from_slice(&bytes[..Self::PACKED_LEN]);
+
+        let mut board = Self::blank();
+        board.unpack_tiles(&packed);
+        if !board.is_valid() {
+            return Err(HnefataflError::InvalidKingCount);
         }
 
-        // Safe to unwrap, if it is none, then we have already returned
-        let p = p.unwrap();
-        let mut moves = Vec::new();
+        board.set_turn(
+            Turn::try_from(bytes[Self::PACKED_LEN]).map_err(|_| HnefataflError::InvalidBytes)?,
+        );
+        board.result = GameResult::from_code(bytes[Self::PACKED_LEN + 1])
+            .ok_or(HnefataflError::InvalidBytes)?;
 
-        // check the square.
-        // Return true if the square is occupied, false if it is empty
-        // (And some logic to handle the fortress)
-        let mut check_square = |x, y| {
-            let check_square = self.get_piece_unchecked(x, y);
+        Ok(board)
+    }
 
-            if check_square.is_none()
-                && (!self.is_fortress(x, y) || (self.is_fortress(x, y) && p == Piece::King))
-            {
-                moves.push((x, y));
-                false
-            } else {
-                true
+    /// Write the position in a notation similar to FEN in chess
+    ///
+    /// The rows are written from the top, separated by `/`, with `A` for attackers, `D` for
+    /// defenders, `K` for the king and a number for consecutive empty tiles. After a space comes
+    /// the side to move, `w` for white or `b` for black.
+    pub fn to_notation(&self) -> String {
+        let mut notation = String::new();
+
+        for (y, row) in self.board.iter().enumerate() {
+            if y > 0 {
+                notation.push('/');
             }
-        };
 
-        // Check up
-        for i in (0..y).rev() {
-            if check_square(x, i) {
-                break;
+            let mut empty = 0;
+            for tile in row {
+                let piece = match tile {
+                    Some(piece) => piece,
+                    None => {
+                        empty += 1;
+                        continue;
+                    }
+                };
+
+                if empty > 0 {
+                    notation.push_str(&empty.to_string());
+                    empty = 0;
+                }
+                notation.push(char::from(*piece));
+            }
+            if empty > 0 {
+                notation.push_str(&empty.to_string());
             }
         }
 
-        // Check down
-        for i in (y + 1)..11 {
-            if check_square(x, i) {
-                break;
-            }
+        notation.push(' ');
+        notation.push(match self.turn {
+            Turn::White => 'w',
+            Turn::Black => 'b',
+        });
+
+        notation
+    }
+
+    /// Create a board from the notation made by [`Board::to_notation`]
+    ///
+    /// The board gets the default rules.
+    pub fn from_notation(s: &str) -> Result<Self, HnefataflError> {
+        let (rows, turn) = s.split_once(' ').ok_or(HnefataflError::InvalidNotation)?;
+        let rows = rows.split('/').collect::<Vec<_>>();
+        if rows.len() != N {
+            return Err(HnefataflError::InvalidNotation);
         }
 
-        // Check left
-        for i in (0..x).rev() {
-            if check_square(i, y) {
-                break;
+        let mut board = Self::blank();
+        board.set_turn(match turn {
+            "w" => Turn::White,
+            "b" => Turn::Black,
+            _ => return Err(HnefataflError::InvalidNotation),
+        });
+
+        for (y, row) in rows.into_iter().enumerate() {
+            let mut x = 0;
+            let mut chars = row.chars().peekable();
+
+            while let Some(c) = chars.next() {
+                if let Some(digit) = c.to_digit(10) {
+                    let mut empty = digit as usize;
+                    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                        empty = empty * 10 + digit as usize;
+                        chars.next();
+                        if empty > N {
+                            return Err(HnefataflError::InvalidNotation);
+                        }
+                    }
+                    if empty == 0 {
+                        return Err(HnefataflError::InvalidNotation);
+                    }
+                    x += empty;
+                    continue;
+                }
+
+                let piece = Piece::try_from(c)?;
+                if x >= N {
+                    return Err(HnefataflError::InvalidNotation);
+                }
+                board.place_piece(piece, x as i32, y as i32);
+                x += 1;
             }
-        }
 
-        // Check right
-        for i in (x + 1)..11 {
-            if check_square(i, y) {
-                break;
+            if x != N {
+                return Err(HnefataflError::InvalidNotation);
             }
         }
 
-        moves
+        if !board.is_valid() {
+            return Err(HnefataflError::InvalidKingCount);
+        }
+
+        Ok(board)
     }
 
-    /// Returns all available moves right now
-    pub fn available_moves(&self) -> Vec<Move> {
-        let mut moves = Vec::new();
-        for x in 0..11 {
-            for y in 0..11 {
-                if let Some(true) = self
-                    .get_piece_unchecked(x, y)
-                    .map(|p| self.turn.is_same_color(&p))
-                {
-                    moves.extend(
-                        self.moves_from(x, y)
-                            .into_iter()
-                            .map(|(to_x, to_y)| Move::from(x, y, to_x, to_y).unwrap()),
-                    );
-                }
-            }
+    /// Get statistics about the game so far
+    pub fn stats(&self) -> GameStats {
+        GameStats {
+            ply: self.ply,
+            legal_moves: self.available_moves().len(),
+            white_captured: self.white_captured,
+            black_captured: self.black_captured,
         }
-        moves
     }
-}
 
-// {{{ Display
+    /// Check if two boards have the same pieces on the same tiles
+    ///
+    /// Unlike `==` this ignores whose turn it is and whether the game is won
+    pub fn same_position(&self, other: &Self) -> bool {
+        self.board == other.board
+    }
 
-impl Display for Board {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Turn: {:?}", self.turn)?;
-        for row in self.board.iter() {
-            for piece in row.iter() {
-                match piece {
-                    Some(Piece::King) => f.write_str("K")?,
-                    Some(Piece::Defender) => f.write_str("D")?,
-                    Some(Piece::Attacker) => f.write_str("A")?,
-                    None => f.write_str("-")?,
-                }
-            }
-            f.write_str("\n")?;
+    /// Get a piece, but do not check if the coordinates are within bounds
+    pub fn get_piece_unchecked(&self, x: i32, y: i32) -> Option<Piece> {
+        self.board[y as usize][x as usize]
+    }
+
+    /// Get a piece, but check if the coordinates are within bounds
+    /// returns None if the coordinates are out of bounds
+    pub fn get_piece_checked(&self, x: i32, y: i32) -> Option<Piece> {
+        if !(0..=Self::LAST).contains(&x) || !(0..=Self::LAST).contains(&y) {
+            return None;
         }
 
-        Ok(())
+        self.board[y as usize][x as usize]
     }
-}
 
-// }}}
+    /// Check if there is a piece on the tile
+    ///
+    /// Returns false if the coordinates are out of bounds
+    pub fn occupied(&self, x: i32, y: i32) -> bool {
+        self.get_piece_checked(x, y).is_some()
+    }
 
-// {{{ Default
+    /// Check if the tile is on the board and has no piece on it
+    ///
+    /// Returns false if the coordinates are out of bounds
+    pub fn is_empty(&self, x: i32, y: i32) -> bool {
+        (0..=Self::LAST).contains(&x)
+            && (0..=Self::LAST).contains(&y)
+            && self.get_piece_unchecked(x, y).is_none()
+    }
 
-impl Default for Board {
-    fn default() -> Self {
-        Self::new()
+    /// Iterate over all the pieces on the board, as `(x, y, piece)`
+    pub fn pieces(&self) -> Pieces<'_, N> {
+        Pieces {
+            board: self,
+            index: 0,
+        }
     }
-}
 
-// }}}
+    /// Iterate over the pieces of one side, as `(x, y, piece)`
+    ///
+    /// White has the defenders and the king, black has the attackers
+    pub fn pieces_of(&self, turn: Turn) -> impl Iterator<Item = (i32, i32, Piece)> + '_ {
+        self.pieces().filter(move |(_, _, p)| p.color() == turn)
+    }
 
-// {{{ Tests
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Iterate over the coordinates of the defenders, not including the king
+    pub fn defenders(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.pieces_of_kind(Piece::Defender)
+    }
 
-    #[test]
-    fn test_board() {
-        let board = Board::new();
+    /// Iterate over the coordinates of the attackers
+    pub fn attackers(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.pieces_of_kind(Piece::Attacker)
+    }
 
-        use Piece::*;
+    /// Iterate over the coordinates of the king, there is at most one in a valid game
+    pub fn kings(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.pieces_of_kind(Piece::King)
+    }
 
-        // {{{ A lot of asserts
-        assert_eq!(board.get_piece_unchecked(0, 0), None);
-        assert_eq!(board.get_piece_unchecked(1, 0), None);
-        assert_eq!(board.get_piece_unchecked(2, 0), None);
-        assert_eq!(board.get_piece_unchecked(3, 0), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(4, 0), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(5, 0), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(6, 0), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(7, 0), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(8, 0), None);
-        assert_eq!(board.get_piece_unchecked(9, 0), None);
-        assert_eq!(board.get_piece_unchecked(10, 0), None);
+    /// Get the coordinates of the king, or `None` if it has been captured
+    pub fn king_position(&self) -> Option<(i32, i32)> {
+        self.kings().next()
+    }
 
-        assert_eq!(board.get_piece_unchecked(0, 1), None);
-        assert_eq!(board.get_piece_unchecked(1, 1), None);
-        assert_eq!(board.get_piece_unchecked(2, 1), None);
-        assert_eq!(board.get_piece_unchecked(3, 1), None);
-        assert_eq!(board.get_piece_unchecked(4, 1), None);
-        assert_eq!(board.get_piece_unchecked(5, 1), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(6, 1), None);
-        assert_eq!(board.get_piece_unchecked(7, 1), None);
-        assert_eq!(board.get_piece_unchecked(8, 1), None);
+    /// Number of steps from the king to the nearest corner, or `None` if it has been captured
+    ///
+    /// This is the Manhattan distance, pieces in the way are not considered
+    pub fn king_distance_to_nearest_corner(&self) -> Option<i32> {
+        let (x, y) = self.king_position()?;
+        Some(x.min(Self::LAST - x) + y.min(Self::LAST - y))
+    }
+
+    fn pieces_of_kind(&self, kind: Piece) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.pieces()
+            .filter(move |(_, _, p)| *p == kind)
+            .map(|(x, y, _)| (x, y))
+    }
+
+    /// Get the board as rows of characters
+    ///
+    /// Pieces are `K`, `D` and `A`. Empty tiles are `.`, except the corners which are `#` and the
+    /// throne which is `+`.
+    pub fn as_2d_chars(&self) -> Vec<Vec<char>> {
+        (0..N as i32)
+            .map(|y| {
+                (0..N as i32)
+                    .map(|x| match self.get_piece_unchecked(x, y) {
+                        Some(piece) => piece.into(),
+                        None if self.is_corner(x, y) => '#',
+                        None if self.is_fortress(x, y) => '+',
+                        None => '.',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Place a piece on the board
+    fn place(&mut self, piece: Option<Piece>, x: i32, y: i32) {
+        self.rehash();
+        if let Some(old) = self.get_piece_unchecked(x, y) {
+            self.zobrist ^= Self::zobrist_key(old, x, y);
+        }
+        if let Some(new) = piece {
+            self.zobrist ^= Self::zobrist_key(new, x, y);
+        }
+        self.board[y as usize][x as usize] = piece;
+    }
+
+    /// Add the tile handed out by `IndexMut` back into the Zobrist hash
+    fn rehash(&mut self) {
+        self.zobrist = self.zobrist();
+        self.unhashed = None;
+    }
+
+    /// The number added to the Zobrist hash when `piece` stands on the tile
+    fn zobrist_key(piece: Piece, x: i32, y: i32) -> u64 {
+        ZOBRIST_PIECES[y as usize * N + x as usize][piece as usize]
+    }
+
+    /// Put a piece on a tile, or clear it with `None`, for setting up positions
+    ///
+    /// Whatever was on the tile is replaced. No rules are checked, so the result may not be a
+    /// position that can be reached in a game.
+    pub fn set_piece(
+        &mut self,
+        piece: Option<Piece>,
+        x: i32,
+        y: i32,
+    ) -> Result<(), HnefataflError> {
+        if !(0..=Self::LAST).contains(&x) || !(0..=Self::LAST).contains(&y) {
+            return Err(HnefataflError::TargetOutOfBounds);
+        }
+
+        self.place(piece, x, y);
+        Ok(())
+    }
+
+    /// place a piece on the board, but do not check if the coordinates are within bounds
+    fn place_piece(&mut self, piece: Piece, x: i32, y: i32) {
+        self.place(Some(piece), x, y);
+    }
+
+    /// Remove a piece from the board
+    fn remove_piece(&mut self, x: i32, y: i32) {
+        self.place(None, x, y);
+    }
+
+    /// Move a piece without checking if the move is valid
+    pub fn move_piece_uncheced(&mut self, x: i32, y: i32, new_x: i32, new_y: i32) {
+        self.place(self.get_piece_unchecked(x, y), new_x, new_y);
+        self.remove_piece(x, y);
+    }
+
+    /// Check if the move is legal right now, without performing it
+    pub fn is_legal(&self, m: &Move) -> bool {
+        let (to_x, to_y) = m.destination();
+        self.validate(m.x, m.y, to_x, to_y).is_ok()
+    }
+
+    /// Check that moving the piece is legal, see [`Board::move_piece`] for the errors
+    fn validate(&self, x: i32, y: i32, new_x: i32, new_y: i32) -> Result<(), HnefataflError> {
+        // Important to check if the bounds are met before trying to access the piece
+        if !(0..=Self::LAST).contains(&x) || !(0..=Self::LAST).contains(&y) {
+            return Err(HnefataflError::StartOutOfBounds);
+        }
+        if !(0..=Self::LAST).contains(&new_x) || !(0..=Self::LAST).contains(&new_y) {
+            return Err(HnefataflError::TargetOutOfBounds);
+        }
+        // A piece has to move somewhere
+        if x == new_x && y == new_y {
+            return Err(HnefataflError::IllegalMove);
+        }
+        // Check if bad direction (gotta be rook move)
+        if x != new_x && y != new_y {
+            return Err(HnefataflError::MoveNotHorVer);
+        }
+        if self.is_game_over() {
+            return Err(HnefataflError::GameAlreadyWon);
+        }
+
+        let piece = self
+            .get_piece_unchecked(x, y)
+            .ok_or(HnefataflError::NoPieceToMove)?;
+
+        if !self.turn.is_same_color(&piece) {
+            return Err(HnefataflError::WrongPieceColor);
+        }
+
+        if piece != Piece::King && self.is_fortress(new_x, new_y) {
+            return Err(HnefataflError::IsProtectedTile);
+        }
+
+        let mv = Move::from(x, y, new_x, new_y).map_err(|_| HnefataflError::IllegalMove)?;
+
+        if let Some((bx, by)) = self.first_blocker(x, y, mv.direction) {
+            let distance = i32::max((bx - x).abs(), (by - y).abs());
+
+            // Only a piece on the target itself, with a free path to it, can be your own
+            if distance == mv.delta
+                && self
+                    .get_piece_unchecked(bx, by)
+                    .is_some_and(|target| piece.is_same_color(&target))
+            {
+                return Err(HnefataflError::OwnPieceAtTarget);
+            }
+            if distance <= mv.delta {
+                return Err(HnefataflError::PieceInTheWay);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Move a piece, checking if the move is valid
+    pub fn move_piece(
+        &mut self,
+        x: i32,
+        y: i32,
+        new_x: i32,
+        new_y: i32,
+    ) -> Result<CompactMove, HnefataflError> {
+        self.validate(x, y, new_x, new_y)?;
+
+        // The move is valid, so there is a piece to move and the direction is fine
+        let piece = self.get_piece_unchecked(x, y).unwrap();
+        let mut mv = Move::from(x, y, new_x, new_y)?;
+
+        let mut entry = HistoryEntry {
+            piece,
+            from: (x, y),
+            to: (new_x, new_y),
+            captured: Vec::new(),
+            turn: self.turn,
+            result: self.result,
+            positions: self.positions.len(),
+        };
+
+        // the position before the first move counts towards repetition as well
+        if self.positions.is_empty() {
+            self.positions.push(self.zobrist());
+        }
+
+        self.remove_piece(x, y);
+        self.place_piece(piece, new_x, new_y);
+
+        // try capture in all directions, then shield walls the moved piece is part of closing
+        let mut capture = |x, y, dir| {
+            let captured = match self.try_capture(x, y, dir) {
+                Some(p) => vec![(x, y, p)],
+                None => self.try_shield_wall(&piece, (new_x, new_y), x, y),
+            };
+
+            for (_, _, p) in &captured {
+                if *p == Piece::King {
+                    mv.set_win();
+                }
+                match p.color() {
+                    Turn::White => self.white_captured += 1,
+                    Turn::Black => self.black_captured += 1,
+                }
+            }
+            if !captured.is_empty() {
+                mv.add_captures(dir, captured.len() as i32).unwrap();
+            }
+            entry.captured.extend(captured);
+        };
+        capture(new_x, new_y + 1, Direction::Up);
+        capture(new_x, new_y - 1, Direction::Down);
+        capture(new_x + 1, new_y, Direction::Right);
+        capture(new_x - 1, new_y, Direction::Left);
+
+        // The king escapes by reaching a corner, the throne does not count
+        if piece == Piece::King && self.is_corner(new_x, new_y) {
+            mv.set_win();
+        }
+
+        if piece == Piece::Attacker && self.king_is_encircled() {
+            mv.set_win();
+        }
+
+        if piece != Piece::Attacker && self.is_exit_fort() {
+            mv.set_win();
+        }
+
+        // The opponent loses if none of its pieces can move
+        if !mv.is_win && self.is_blocked(piece.color().opposite()) {
+            mv.set_win();
+        }
+
+        // Every win condition is met by the side that moved
+        if !mv.is_win {
+            self.set_turn(self.turn.opposite());
+        } else {
+            self.result = GameResult::win_for(piece.color());
+        }
+        self.ply += 1;
+        self.positions.push(self.zobrist());
+        self.history.push(entry);
+
+        if !mv.is_win && self.is_draw_by_repetition() {
+            self.result = GameResult::Draw;
+        }
+
+        Ok(mv.into())
+    }
+
+    /// Pack a move between two tiles, without performing it
+    ///
+    /// Only the bounds and the direction of the move are checked, captures are not computed
+    pub fn move_to_compact(
+        &self,
+        from: (i32, i32),
+        to: (i32, i32),
+    ) -> Result<CompactMove, HnefataflError> {
+        if !(0..=Self::LAST).contains(&from.0) || !(0..=Self::LAST).contains(&from.1) {
+            return Err(HnefataflError::StartOutOfBounds);
+        }
+        if !(0..=Self::LAST).contains(&to.0) || !(0..=Self::LAST).contains(&to.1) {
+            return Err(HnefataflError::TargetOutOfBounds);
+        }
+
+        Ok(Move::from(from.0, from.1, to.0, to.1)?.compact())
+    }
+
+    pub fn do_move(&mut self, m: &Move) -> Result<CompactMove, HnefataflError> {
+        let (to_x, to_y) = m.destination();
+        self.move_piece(m.x, m.y, to_x, to_y)
+    }
+
+    /// Perform a move, returning the captured pieces and where they stood
+    ///
+    /// Unlike [`Board::do_move`] this tells which pieces were removed, not only in which
+    /// directions, which includes the pieces of a shield wall
+    pub fn apply(&mut self, m: &Move) -> Result<Vec<(i32, i32, Piece)>, HnefataflError> {
+        self.do_move(m)?;
+
+        Ok(self
+            .history
+            .last()
+            .map(|entry| entry.captured.clone())
+            .unwrap_or_default())
+    }
+
+    /// Take back the last move performed
+    ///
+    /// The captured pieces are put back, and the turn, result and counters are as before the move
+    pub fn undo(&mut self) -> Result<(), HnefataflError> {
+        let entry = self.history.pop().ok_or(HnefataflError::NothingToUndo)?;
+
+        self.remove_piece(entry.to.0, entry.to.1);
+        self.place_piece(entry.piece, entry.from.0, entry.from.1);
+
+        for (x, y, p) in entry.captured {
+            self.place_piece(p, x, y);
+            match p.color() {
+                Turn::White => self.white_captured -= 1,
+                Turn::Black => self.black_captured -= 1,
+            }
+        }
+
+        self.set_turn(entry.turn);
+        self.result = entry.result;
+        self.ply -= 1;
+        self.positions.truncate(entry.positions);
+
+        Ok(())
+    }
+
+    /// Perform the moves in order
+    ///
+    /// Stops at the first move that fails, returning its index and the error.
+    /// The moves before it stay performed.
+    pub fn apply_moves(&mut self, moves: &[Move]) -> Result<(), (usize, HnefataflError)> {
+        for (i, m) in moves.iter().enumerate() {
+            self.do_move(m).map_err(|e| (i, e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Perform a move on a copy of the board
+    ///
+    /// Returns the new board and the outcome of the game after the move
+    pub fn step(&self, m: &Move) -> Result<(Self, GameResult), HnefataflError> {
+        let mut board = self.clone();
+        board.do_move(m)?;
+        let outcome = board.result();
+
+        Ok((board, outcome))
+    }
+
+    /// Check if the move wins the game, without changing the board
+    ///
+    /// Illegal moves are never winning
+    pub fn is_move_winning(&self, m: &Move) -> bool {
+        let mut board = self.clone();
+        board.do_move(m).is_ok() && board.result == GameResult::win_for(self.turn)
+    }
+
+    /// Find a move that immediately wins the game for `turn`, if there is one
+    pub fn has_winning_move(&self, turn: Turn) -> Option<Move> {
+        let mut board = self.clone();
+        board.set_turn(turn);
+
+        board
+            .available_moves()
+            .into_iter()
+            .find(|m| board.is_move_winning(m))
+    }
+
+    /// Find an attacker move that captures the king, if there is one
+    ///
+    /// This is checked for the attackers no matter whose turn it is
+    pub fn king_capturable_next(&self) -> Option<Move> {
+        let mut board = self.clone();
+        board.set_turn(Turn::Black);
+
+        board.available_moves().into_iter().find(|m| {
+            board
+                .step(m)
+                .is_ok_and(|(next, _)| next.king_position().is_none())
+        })
+    }
+
+    /// Find the tiles where `turn` could capture a piece with its next move
+    ///
+    /// Every move of `turn` is tried, whether or not it is its turn. Each tile is listed once,
+    /// sorted by coordinates.
+    pub fn capture_targets(&self, turn: Turn) -> Vec<(i32, i32)> {
+        let mut board = self.clone();
+        board.set_turn(turn);
+
+        let mut targets = Vec::new();
+        for m in board.available_moves() {
+            if let Ok(captured) = board.apply(&m) {
+                targets.extend(captured.into_iter().map(|(x, y, _)| (x, y)));
+                // the move was just performed, so there is something to undo
+                board.undo().unwrap();
+            }
+        }
+
+        targets.sort();
+        targets.dedup();
+        targets
+    }
+
+    /// Check if the tile is a fortress tile, either a corner or the throne.
+    ///
+    /// Only the king may occupy a fortress.
+    ///
+    /// The arguments are not checked if they are within bounds
+    pub(crate) fn is_fortress(&self, x: i32, y: i32) -> bool {
+        self.is_corner(x, y) || self.is_throne(x, y)
+    }
+
+    /// Check if the tile is one of the four corners.
+    ///
+    /// The king wins by reaching a corner.
+    ///
+    /// The arguments are not checked if they are within bounds
+    pub(crate) fn is_corner(&self, x: i32, y: i32) -> bool {
+        (x == 0 || x == Self::LAST) && (y == 0 || y == Self::LAST)
+    }
+
+    /// Check if the tile is the throne in the center of the board.
+    ///
+    /// The center is only a throne if the rules have a throne.
+    pub(crate) fn is_throne(&self, x: i32, y: i32) -> bool {
+        self.rules.has_throne && (x, y) == (Self::CENTER, Self::CENTER)
+    }
+
+    /// Checks if the specified tile is an enemy tile
+    ///
+    /// Returns false if the tile is out of bounds
+    fn is_enemy(&self, start_piece: &Piece, x: i32, y: i32) -> bool {
+        if !(0..=Self::LAST).contains(&x) || !(0..=Self::LAST).contains(&y) {
+            return false;
+        }
+
+        let check_square = self.get_piece_unchecked(x, y);
+
+        // if the king occupies a fortress, then the position is not an enemy to the white pieces
+        // This choice could possibly be changed
+        if let Some(piece) = check_square {
+            // an unarmed king does not help capturing
+            !start_piece.is_same_color(&piece) && (piece != Piece::King || self.rules.armed_king)
+        } else {
+            // the empty throne is hostile to the king only if the rules say so
+            if self.is_throne(x, y) && *start_piece == Piece::King {
+                return self.rules.hostile_throne;
+            }
+            // if the square is empty, but is a fortress, then it is an enemy to all pieces
+            // (unless the rules say corners are not hostile)
+            // if it is an empty, ordinary tile, then it is not an enemy
+            self.is_fortress(x, y) && (self.rules.corners_hostile || !self.is_corner(x, y))
+        }
+    }
+
+    /// Check if the king could ever reach a corner, if the attackers stood still
+    ///
+    /// Defenders are assumed to be able to move out of the way, so this only returns false when
+    /// the attackers have walled the king off from every corner. Then the defenders can not win.
+    pub fn could_king_escape(&self) -> bool {
+        self.king_can_reach(|x, y| self.is_corner(x, y))
+            .unwrap_or(false)
+    }
+
+    /// Check if the attackers have closed a ring around the king
+    ///
+    /// The king is encircled when it can not reach any edge of the board, even if the defenders
+    /// moved out of the way. Any defenders inside the ring are encircled with it.
+    /// The king must be inside the ring, defenders enclosed without the king do not count.
+    /// With [`RuleSet::encircle_defenders`] every defender must be inside the ring as well.
+    pub fn king_is_encircled(&self) -> bool {
+        let edge = |x, y| x == 0 || x == Self::LAST || y == 0 || y == Self::LAST;
+
+        self.king_can_reach(edge) == Some(false)
+            && (!self.rules.encircle_defenders
+                || self
+                    .pieces_of(Turn::White)
+                    .all(|(x, y, _)| !self.can_reach(x, y, edge)))
+    }
+
+    /// Check if the king has built an exit fort on the edge of the board
+    ///
+    /// The king must be on the edge with an empty tile next to it, and the empty tiles around
+    /// the king must be walled in by defenders only. No defender in the wall may be capturable,
+    /// which is checked for the position as it stands: a wall defender is safe when one side of
+    /// it along each line is off the board, inside the fort or a friendly piece.
+    pub fn is_exit_fort(&self) -> bool {
+        let (king_x, king_y) = match self.king_position() {
+            Some(king) => king,
+            None => return false,
+        };
+
+        if !(king_x == 0 || king_x == Self::LAST || king_y == 0 || king_y == Self::LAST) {
+            return false;
+        }
+
+        let neighbours = |x: i32, y: i32| {
+            [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+                .into_iter()
+                .filter(|(x, y)| (0..=Self::LAST).contains(x) && (0..=Self::LAST).contains(y))
+        };
+
+        if !neighbours(king_x, king_y).any(|(x, y)| self.is_empty(x, y)) {
+            return false;
+        }
+
+        // the fort is the king and the empty tiles it can reach
+        let mut inside = [[false; N]; N];
+        let mut wall = Vec::new();
+        let mut queue = vec![(king_x, king_y)];
+        inside[king_y as usize][king_x as usize] = true;
+
+        while let Some((x, y)) = queue.pop() {
+            for (nx, ny) in neighbours(x, y) {
+                if inside[ny as usize][nx as usize] {
+                    continue;
+                }
+
+                match self.get_piece_unchecked(nx, ny) {
+                    None => {
+                        inside[ny as usize][nx as usize] = true;
+                        queue.push((nx, ny));
+                    }
+                    Some(Piece::Defender) => wall.push((nx, ny)),
+                    Some(_) => return false,
+                }
+            }
+        }
+
+        // an attacker could stand on the tile, or the tile is hostile
+        let attackable = |x: i32, y: i32| {
+            (0..=Self::LAST).contains(&x)
+                && (0..=Self::LAST).contains(&y)
+                && !inside[y as usize][x as usize]
+                && match self.get_piece_unchecked(x, y) {
+                    Some(piece) => piece == Piece::Attacker,
+                    None => true,
+                }
+        };
+
+        let flankable = |(x1, y1), (x2, y2)| attackable(x1, y1) && attackable(x2, y2);
+
+        wall.into_iter()
+            .all(|(x, y)| !flankable((x - 1, y), (x + 1, y)) && !flankable((x, y - 1), (x, y + 1)))
+    }
+
+    /// Flood fill from the king, with attackers as walls, until a tile matching `target` is found
+    ///
+    /// Returns nothing if there is no king
+    fn king_can_reach(&self, target: impl Fn(i32, i32) -> bool) -> Option<bool> {
+        let (king_x, king_y) = self.king_position()?;
+        Some(self.can_reach(king_x, king_y, target))
+    }
+
+    /// Flood fill from (x, y), with attackers as walls, until a tile matching `target` is found
+    fn can_reach(&self, x: i32, y: i32, target: impl Fn(i32, i32) -> bool) -> bool {
+        let mut visited = [[false; N]; N];
+        let mut queue = std::collections::VecDeque::from([(x, y)]);
+        visited[y as usize][x as usize] = true;
+
+        while let Some((x, y)) = queue.pop_front() {
+            if target(x, y) {
+                return true;
+            }
+
+            for (nx, ny) in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                if (0..=Self::LAST).contains(&nx)
+                    && (0..=Self::LAST).contains(&ny)
+                    && !visited[ny as usize][nx as usize]
+                    && self.get_piece_unchecked(nx, ny) != Some(Piece::Attacker)
+                {
+                    visited[ny as usize][nx as usize] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Find the fewest tiles the attackers must occupy to cut the king off from the edge
+    ///
+    /// Returns 0 if the king is already walled in by attackers, and `None` if there is no king or
+    /// if it can not be walled in (the king is on the edge).
+    /// An empty tile that attackers may stand on counts once, and a tile with a defender counts
+    /// twice, as the defender must be captured before an attacker can take its place. Opponent
+    /// moves are ignored, so this is a lower bound on the number of attacker moves needed.
+    pub fn shortest_wall_gap(&self) -> Option<usize> {
+        // Maximum flow through a graph where every tile is split into an in and an out node, the
+        // capacity between them is how many attacker moves it takes to block the tile.
+        // The minimum cut, and so the maximum flow, is the moves attackers must make.
+        const BLOCKED: i32 = 0;
+        const DEFENDED: i32 = 2;
+        const UNBLOCKABLE: i32 = 1000;
+
+        let (king_x, king_y) = self.king_position()?;
+
+        let tiles = N * N;
+        let sink = 2 * tiles;
+        let nodes = sink + 1;
+        let index = |x: i32, y: i32| y as usize * N + x as usize;
+
+        let mut capacity = vec![vec![0; nodes]; nodes];
+        for y in 0..N as i32 {
+            for x in 0..N as i32 {
+                let i = index(x, y);
+                capacity[2 * i][2 * i + 1] = match self.get_piece_unchecked(x, y) {
+                    Some(Piece::Attacker) => BLOCKED,
+                    Some(Piece::Defender) => DEFENDED,
+                    Some(Piece::King) => UNBLOCKABLE,
+                    None if self.is_fortress(x, y) => UNBLOCKABLE,
+                    None => 1,
+                };
+
+                for (nx, ny) in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                    if (0..=Self::LAST).contains(&nx) && (0..=Self::LAST).contains(&ny) {
+                        capacity[2 * i + 1][2 * index(nx, ny)] = UNBLOCKABLE;
+                    } else {
+                        capacity[2 * i + 1][sink] = UNBLOCKABLE;
+                    }
+                }
+            }
+        }
+
+        let source = 2 * index(king_x, king_y) + 1;
+        let mut flow = 0;
+
+        // Edmonds-Karp: augment along shortest paths until the sink can not be reached
+        loop {
+            let mut previous = vec![None; nodes];
+            let mut queue = std::collections::VecDeque::from([source]);
+            previous[source] = Some(source);
+
+            while let Some(node) = queue.pop_front() {
+                for next in 0..nodes {
+                    if previous[next].is_none() && capacity[node][next] > 0 {
+                        previous[next] = Some(node);
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            if previous[sink].is_none() {
+                break;
+            }
+
+            let mut bottleneck = UNBLOCKABLE;
+            let mut node = sink;
+            while node != source {
+                let prev = previous[node].unwrap();
+                bottleneck = bottleneck.min(capacity[prev][node]);
+                node = prev;
+            }
+
+            let mut node = sink;
+            while node != source {
+                let prev = previous[node].unwrap();
+                capacity[prev][node] -= bottleneck;
+                capacity[node][prev] += bottleneck;
+                node = prev;
+            }
+
+            flow += bottleneck;
+            if flow >= UNBLOCKABLE {
+                return None;
+            }
+        }
+
+        Some(flow as usize)
+    }
+
+    /// Count the sides of the piece on the tile that are hostile to it
+    ///
+    /// Uses the same rules as capturing, so empty fortresses count as hostile.
+    /// Returns 0 if there is no piece on the tile.
+    pub fn surrounding_enemy_count(&self, x: i32, y: i32) -> u8 {
+        let piece = match self.get_piece_checked(x, y) {
+            Some(piece) => piece,
+            None => return 0,
+        };
+
+        [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+            .iter()
+            .filter(|(x, y)| self.is_enemy(&piece, *x, *y))
+            .count() as u8
+    }
+
+    /// Try to capture a piece.
+    /// If this piece is captured, then return the piece.
+    fn try_capture(&mut self, x: i32, y: i32, direction: Direction) -> Option<Piece> {
+        let p = self.get_piece_checked(x, y)?;
+
+        use Direction::*;
+
+        let (lx, ly) = match direction {
+            Up | Down => (x, y - 1),
+            Left | Right => (x - 1, y),
+        };
+
+        let (rx, ry) = match direction {
+            Up | Down => (x, y + 1),
+            Left | Right => (x + 1, y),
+        };
+
+        // with throne immunity, the king on the throne does not help capturing
+        let king_on_throne_anvil = self.rules.throne_immunity
+            && self.is_king_on_throne()
+            && (self.is_throne(lx, ly) || self.is_throne(rx, ry));
+
+        // checking for normal capture
+        if p != Piece::King
+            && !king_on_throne_anvil
+            && self.is_enemy(&p, lx, ly)
+            && self.is_enemy(&p, rx, ry)
+        {
+            self.remove_piece(x, y);
+            return Some(p);
+        }
+
+        // King capture, where the edge of the board may count as hostile
+        let king_hostile = |x: i32, y: i32| {
+            self.is_enemy(&p, x, y)
+                || (self.rules.edge_king_capture
+                    && !((0..=Self::LAST).contains(&x) && (0..=Self::LAST).contains(&y)))
+        };
+        if p == Piece::King
+            && king_hostile(x + 1, y)
+            && king_hostile(x - 1, y)
+            && king_hostile(x, y + 1)
+            && king_hostile(x, y - 1)
+        {
+            self.remove_piece(x, y);
+            return Some(p);
+        }
+
+        None
+    }
+
+    /// Try to capture a shield wall, a line of two or more pieces along the edge of the board
+    ///
+    /// The tile (x, y) is next to the piece that moved, `mover`, standing on `at`. The line of
+    /// enemy pieces containing it is captured if the mover closes one end of it, there is an
+    /// enemy at the other end, and an enemy in front of every piece. The king survives in the
+    /// wall, unless the rules say otherwise.
+    ///
+    /// Returns the captured pieces and where they stood
+    fn try_shield_wall(
+        &mut self,
+        mover: &Piece,
+        at: (i32, i32),
+        x: i32,
+        y: i32,
+    ) -> Vec<(i32, i32, Piece)> {
+        let p = match self.get_piece_checked(x, y) {
+            Some(p) if !mover.is_same_color(&p) => p,
+            _ => return Vec::new(),
+        };
+
+        // the direction along the edge, and the direction to the front of the wall
+        let ((dx, dy), (fx, fy)) = if y == 0 {
+            ((1, 0), (0, 1))
+        } else if y == Self::LAST {
+            ((1, 0), (0, -1))
+        } else if x == 0 {
+            ((0, 1), (1, 0))
+        } else if x == Self::LAST {
+            ((0, 1), (-1, 0))
+        } else {
+            return Vec::new();
+        };
+
+        let in_wall = |(x, y): (i32, i32)| {
+            self.get_piece_checked(x, y)
+                .is_some_and(|q| q.is_same_color(&p))
+        };
+
+        let (mut start_x, mut start_y) = (x, y);
+        while in_wall((start_x - dx, start_y - dy)) {
+            start_x -= dx;
+            start_y -= dy;
+        }
+
+        let mut wall = Vec::new();
+        let (mut end_x, mut end_y) = (start_x, start_y);
+        while in_wall((end_x, end_y)) {
+            wall.push((
+                end_x,
+                end_y,
+                self.get_piece_unchecked(end_x, end_y).unwrap(),
+            ));
+            end_x += dx;
+            end_y += dy;
+        }
+
+        let (first, last) = (wall[0].2, wall[wall.len() - 1].2);
+        let closes_end = at == (start_x - dx, start_y - dy) || at == (end_x, end_y);
+        if !closes_end
+            || wall.len() < 2
+            || !self.is_enemy(&first, start_x - dx, start_y - dy)
+            || !self.is_enemy(&last, end_x, end_y)
+            || !wall
+                .iter()
+                .all(|(x, y, q)| self.is_enemy(q, x + fx, y + fy))
+        {
+            return Vec::new();
+        }
+
+        let mut captured = Vec::new();
+        for (x, y, q) in wall {
+            if q != Piece::King || self.rules.shield_wall_captures_king {
+                self.remove_piece(x, y);
+                captured.push((x, y, q));
+            }
+        }
+
+        captured
+    }
+
+    /// Returns a list of all target tiles available from the specified tile
+    /// This does check whose turn it is
+    fn moves_from(&self, x: i32, y: i32) -> Vec<(i32, i32)> {
+        if !(0..=Self::LAST).contains(&x) || !(0..=Self::LAST).contains(&y) {
+            return Vec::new();
+        }
+
+        // We have already checked the bounds, no need to check again
+        let p = self.get_piece_unchecked(x, y);
+        if p.is_none() || !self.turn.is_same_color(&p.unwrap()) {
+            return Vec::new();
+        }
+
+        // Safe to unwrap, if it is none, then we have already returned
+        let p = p.unwrap();
+        let mut moves = Vec::new();
+
+        use Direction::*;
+
+        for direction in [Up, Down, Left, Right] {
+            let (mut i, mut j) = (x + direction.x(), y + direction.y());
+            let blocker = self.first_blocker(x, y, direction);
+
+            // Only the king may stop on or pass over a fortress
+            while (0..=Self::LAST).contains(&i)
+                && (0..=Self::LAST).contains(&j)
+                && Some((i, j)) != blocker
+                && (p == Piece::King || !self.is_fortress(i, j))
+            {
+                moves.push((i, j));
+                i += direction.x();
+                j += direction.y();
+            }
+        }
+
+        moves
+    }
+
+    /// Find the first occupied tile from the specified tile in the given direction
+    ///
+    /// The starting tile is not included. Returns nothing if the ray reaches the edge of the board.
+    pub fn first_blocker(&self, x: i32, y: i32, direction: Direction) -> Option<(i32, i32)> {
+        let (mut x, mut y) = (x + direction.x(), y + direction.y());
+
+        while (0..=Self::LAST).contains(&x) && (0..=Self::LAST).contains(&y) {
+            if self.get_piece_unchecked(x, y).is_some() {
+                return Some((x, y));
+            }
+
+            x += direction.x();
+            y += direction.y();
+        }
+
+        None
+    }
+
+    /// Follow a ray from the specified tile in the given direction
+    ///
+    /// Returns the tiles passed and their contents, up to and including the first occupied tile.
+    /// The starting tile is not included, and the ray stops at the edge of the board.
+    pub fn ray(&self, x: i32, y: i32, direction: Direction) -> Vec<((i32, i32), Option<Piece>)> {
+        let mut ray = Vec::new();
+        let (mut x, mut y) = (x + direction.x(), y + direction.y());
+
+        while (0..=Self::LAST).contains(&x) && (0..=Self::LAST).contains(&y) {
+            let piece = self.get_piece_unchecked(x, y);
+            ray.push(((x, y), piece));
+
+            if piece.is_some() {
+                break;
+            }
+
+            x += direction.x();
+            y += direction.y();
+        }
+
+        ray
+    }
+
+    /// Find the pieces of `turn` that could move to the specified tile
+    ///
+    /// Returns the coordinates of the pieces, or nothing if the tile is occupied or out of bounds
+    pub fn pieces_attacking_square(&self, x: i32, y: i32, turn: Turn) -> Vec<(i32, i32)> {
+        if !self.is_empty(x, y) {
+            return Vec::new();
+        }
+
+        use Direction::*;
+
+        [Up, Right, Down, Left]
+            .into_iter()
+            .filter_map(|direction| {
+                let ray = self.ray(x, y, direction);
+                let (&((px, py), piece), passed) = ray.split_last()?;
+                let piece = piece?;
+
+                // only the king may pass or stop on a fortress
+                let fortress_in_path = std::iter::once((x, y))
+                    .chain(passed.iter().map(|(tile, _)| *tile))
+                    .any(|(x, y)| self.is_fortress(x, y));
+
+                (piece.color() == turn && (piece == Piece::King || !fortress_in_path))
+                    .then_some((px, py))
+            })
+            .collect()
+    }
+
+    /// Check if the side to move has any move at all
+    pub fn has_legal_moves(&self) -> bool {
+        self.pieces_of(self.turn)
+            .any(|(x, y, piece)| self.can_move(piece, x, y))
+    }
+
+    /// Check if `turn` has pieces on the board, but none of them can move
+    ///
+    /// Such a side loses the game. A side without pieces is not counted as blocked, since the
+    /// game is decided by the king long before a side runs out of pieces.
+    fn is_blocked(&self, turn: Turn) -> bool {
+        self.pieces_of(turn).next().is_some()
+            && !self
+                .pieces_of(turn)
+                .any(|(x, y, piece)| self.can_move(piece, x, y))
+    }
+
+    /// Check if the piece on (x, y) can move, no matter whose turn it is
+    ///
+    /// It can if one of the tiles next to it is free for it to stop on
+    fn can_move(&self, piece: Piece, x: i32, y: i32) -> bool {
+        use Direction::*;
+
+        [Up, Down, Left, Right].into_iter().any(|direction| {
+            let (i, j) = (x + direction.x(), y + direction.y());
+            self.is_empty(i, j) && (piece == Piece::King || !self.is_fortress(i, j))
+        })
+    }
+
+    /// Returns all available moves right now
+    pub fn available_moves(&self) -> Vec<Move> {
+        self.available_moves_filtered(|_| true)
+    }
+
+    /// Pick one of the available moves at random
+    ///
+    /// Returns `None` if there are no moves or the game is over
+    #[cfg(feature = "rand")]
+    pub fn random_move(&self, rng: &mut impl rand::Rng) -> Option<Move> {
+        use rand::seq::SliceRandom;
+
+        if self.is_game_over() {
+            return None;
+        }
+
+        self.available_moves().choose(rng).copied()
+    }
+
+    /// Returns the available moves that do not throw the game away
+    ///
+    /// Unlike [`Board::available_moves`], which only looks at how the pieces move, no moves are
+    /// returned once the game is over, and king moves to a tile where the attackers can capture
+    /// the king with their next move are left out.
+    pub fn available_moves_checked(&self) -> Vec<Move> {
+        if self.is_game_over() {
+            return Vec::new();
+        }
+
+        self.available_moves()
+            .into_iter()
+            .filter(|m| {
+                self.get_piece_unchecked(m.x, m.y) != Some(Piece::King)
+                    || self.step(m).is_ok_and(|(next, result)| {
+                        result != GameResult::Ongoing || next.king_capturable_next().is_none()
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns all available moves right now for the pieces accepted by `filter`
+    pub fn available_moves_filtered(&self, filter: impl Fn(Piece) -> bool) -> Vec<Move> {
+        let mut moves = Vec::new();
+        for x in 0..N as i32 {
+            for y in 0..N as i32 {
+                if let Some(true) = self
+                    .get_piece_unchecked(x, y)
+                    .map(|p| self.turn.is_same_color(&p) && filter(p))
+                {
+                    moves.extend(
+                        self.moves_from(x, y)
+                            .into_iter()
+                            .map(|(to_x, to_y)| Move::from(x, y, to_x, to_y).unwrap()),
+                    );
+                }
+            }
+        }
+        moves
+    }
+
+    /// Returns all available moves right now, with their captures and wins filled in
+    ///
+    /// Winning moves come first, then moves by how many pieces they capture, then the rest
+    pub fn legal_moves_sorted(&self) -> Vec<Move> {
+        let mut moves = self.annotated_moves();
+        moves.sort_by_key(|m| std::cmp::Reverse(m.priority()));
+        moves
+    }
+
+    /// Returns all available moves right now, with their captures and wins filled in
+    fn annotated_moves(&self) -> Vec<Move> {
+        self.available_moves()
+            .iter()
+            .filter_map(|m| self.clone().do_move(m).ok())
+            .map(Into::into)
+            .collect()
+    }
+
+    /// Check if the side to move has no capturing moves available
+    ///
+    /// Search can stop extending a line once the position is quiet
+    pub fn is_quiet(&self) -> bool {
+        !self
+            .available_moves()
+            .iter()
+            .any(|m| self.clone().do_move(m).is_ok_and(|c| c.capture_count() > 0))
+    }
+
+    /// Count the available moves for both sides, as `(white, black)`
+    ///
+    /// The counts do not depend on whose turn it is
+    pub fn legal_move_count_by_side(&self) -> (usize, usize) {
+        (self.mobility(Turn::White), self.mobility(Turn::Black))
+    }
+
+    /// Count the moves available to `turn`, as if it was their turn
+    pub fn mobility(&self, turn: Turn) -> usize {
+        let mut board = self.clone();
+        board.set_turn(turn);
+        board.available_moves().len()
+    }
+
+    /// Count the pieces of `turn` on the board
+    ///
+    /// The king is not counted, since losing it loses the game rather than material
+    pub fn material(&self, turn: Turn) -> i32 {
+        self.pieces_of(turn)
+            .filter(|(_, _, p)| *p != Piece::King)
+            .count() as i32
+    }
+
+    /// Count the ways to play `depth` moves from this position, to check the move generation
+    ///
+    /// Every move is performed and taken back again. Games that end before `depth` moves are not
+    /// counted, as no more moves can be played in them.
+    pub fn perft(&self, depth: u32) -> u64 {
+        self.clone().perft_mut(depth)
+    }
+
+    fn perft_mut(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        if self.is_game_over() {
+            return 0;
+        }
+
+        let mut count = 0;
+        for m in self.available_moves() {
+            if self.do_move(&m).is_ok() {
+                count += self.perft_mut(depth - 1);
+                self.undo().unwrap();
+            }
+        }
+
+        count
+    }
+
+    /// Write a move in short notation: the piece, the start tile only if needed, then the target
+    ///
+    /// Files are the letters `a` to `k` from the left, ranks are the numbers 1 to N from the
+    /// bottom. The start tile is added when another piece of the same kind could move to the same
+    /// target, using the file, the rank or both, whichever tells the pieces apart.
+    /// For instance `Ad4`, `Abd4`, `A7d4` or `Ab7d4`.
+    pub fn disambiguate(&self, m: &Move) -> String {
+        let (to_x, to_y) = m.destination();
+        let target = format!("{}{}", file_name(to_x), rank_name(to_y, N));
+
+        let piece = match self.get_piece_checked(m.x, m.y) {
+            Some(piece) => piece,
+            None => return format!("{}{}{}", file_name(m.x), rank_name(m.y, N), target),
+        };
+
+        let mut board = self.clone();
+        board.set_turn(piece.color());
+        let others = board
+            .available_moves_filtered(|p| p == piece)
+            .into_iter()
+            .filter(|o| o.destination() == (to_x, to_y) && (o.x, o.y) != (m.x, m.y))
+            .collect::<Vec<_>>();
+
+        let letter = char::from(piece);
+
+        if others.is_empty() {
+            format!("{}{}", letter, target)
+        } else if others.iter().all(|o| o.x != m.x) {
+            format!("{}{}{}", letter, file_name(m.x), target)
+        } else if others.iter().all(|o| o.y != m.y) {
+            format!("{}{}{}", letter, rank_name(m.y, N), target)
+        } else {
+            format!(
+                "{}{}{}{}",
+                letter,
+                file_name(m.x),
+                rank_name(m.y, N),
+                target
+            )
+        }
+    }
+
+    /// Read a move written by [`Board::disambiguate`], like `Ad4` or `Ab7d4`
+    ///
+    /// A piece of the kind named must be able to move to the target, and the start tile must
+    /// tell it apart from any other piece of that kind that can move there.
+    pub fn parse_move(&self, s: &str) -> Result<Move, HnefataflError> {
+        let split = s
+            .rfind(|c: char| c.is_ascii_lowercase())
+            .ok_or(HnefataflError::InvalidNotation)?;
+        let (start, target) = s.split_at(split);
+        let (to_x, to_y) = parse_tile(target, N)?;
+
+        let (piece, start) = match start.chars().next() {
+            Some(letter) if letter.is_ascii_uppercase() => {
+                (Some(Piece::try_from(letter)?), &start[1..])
+            }
+            _ => (None, start),
+        };
+        let (file, rank) = match start.chars().next() {
+            Some(file) if file.is_ascii_lowercase() => (Some(parse_file(file, N)?), &start[1..]),
+            _ => (None, start),
+        };
+        let rank = match rank {
+            "" => None,
+            rank => Some(parse_rank(rank, N)?),
+        };
+
+        let piece = match piece {
+            Some(piece) => piece,
+            // moves from an empty tile are written with the whole start tile
+            None => {
+                return match (file, rank) {
+                    (Some(x), Some(y)) => Move::from(x, y, to_x, to_y),
+                    _ => Err(HnefataflError::InvalidNotation),
+                }
+            }
+        };
+
+        let mut board = self.clone();
+        board.set_turn(piece.color());
+        let mut candidates = board
+            .available_moves_filtered(|p| p == piece)
+            .into_iter()
+            .filter(|m| {
+                m.destination() == (to_x, to_y)
+                    && file.is_none_or(|x| m.x == x)
+                    && rank.is_none_or(|y| m.y == y)
+            });
+
+        match (candidates.next(), candidates.next()) {
+            (Some(m), None) => Ok(m),
+            _ => Err(HnefataflError::InvalidNotation),
+        }
+    }
+}
+
+/// Name of a column in notation, `a` is the leftmost column
+fn file_name(x: i32) -> char {
+    (b'a' + x as u8) as char
+}
+
+/// Name of a row in notation on a board of the given size, 1 is the bottom row
+fn rank_name(y: i32, size: usize) -> i32 {
+    size as i32 - y
+}
+
+/// Parse a tile written as its file and rank, like `d4`, on a board of the given size
+fn parse_tile(s: &str, size: usize) -> Result<(i32, i32), HnefataflError> {
+    let mut chars = s.chars();
+    let file = chars.next().ok_or(HnefataflError::InvalidNotation)?;
+    Ok((parse_file(file, size)?, parse_rank(chars.as_str(), size)?))
+}
+
+/// Parse the name of a column, see [`file_name`]
+fn parse_file(file: char, size: usize) -> Result<i32, HnefataflError> {
+    let x = file as i32 - 'a' as i32;
+    if file.is_ascii_lowercase() && (0..size as i32).contains(&x) {
+        Ok(x)
+    } else {
+        Err(HnefataflError::InvalidNotation)
+    }
+}
+
+/// Parse the name of a row, see [`rank_name`]
+fn parse_rank(rank: &str, size: usize) -> Result<i32, HnefataflError> {
+    if !rank.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(HnefataflError::InvalidNotation);
+    }
+    let rank = rank
+        .parse::<i32>()
+        .map_err(|_| HnefataflError::InvalidNotation)?;
+    if !(1..=size as i32).contains(&rank) {
+        return Err(HnefataflError::InvalidNotation);
+    }
+
+    Ok(rank_name(rank, size))
+}
+
+// {{{ Builder
+
+/// Set up a board piece by piece, for puzzles and other positions from the middle of a game
+///
+/// The position is checked when the board is built, see [`BoardBuilder::build`]
+#[derive(Debug, Clone)]
+pub struct BoardBuilder<const N: usize = 11> {
+    pieces: Vec<(i32, i32, Piece)>,
+    turn: Turn,
+    result: GameResult,
+    rules: RuleSet,
+}
+
+impl<const N: usize> Default for BoardBuilder<N> {
+    fn default() -> Self {
+        Self {
+            pieces: Vec::new(),
+            turn: Turn::Black,
+            result: GameResult::Ongoing,
+            rules: RuleSet::for_size(N),
+        }
+    }
+}
+
+impl<const N: usize> BoardBuilder<N> {
+    /// Start with an empty board where black moves first and the rules for its size
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Put a piece on a tile
+    pub fn piece(mut self, piece: Piece, x: i32, y: i32) -> Self {
+        self.pieces.push((x, y, piece));
+        self
+    }
+
+    /// Set whose turn it is
+    pub fn turn(mut self, turn: Turn) -> Self {
+        self.turn = turn;
+        self
+    }
+
+    /// Set the result of the game, to set up a game that is already over
+    pub fn result(mut self, result: GameResult) -> Self {
+        self.result = result;
+        self
+    }
+
+    /// Set the rules the game is played by
+    pub fn rules(mut self, rules: RuleSet) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Build the board
+    ///
+    /// Fails if a piece is outside the board, two pieces are put on the same tile, a piece other
+    /// than the king is on a fortress, there is not exactly one king, or there are more pieces
+    /// than the rules allow.
+    pub fn build(&self) -> Result<Board<N>, HnefataflError> {
+        let mut board = Board::blank();
+        board.set_rules(self.rules);
+
+        for &(x, y, piece) in &self.pieces {
+            if board.get_piece_checked(x, y).is_some() {
+                return Err(HnefataflError::TileOccupied);
+            }
+            board.set_piece(Some(piece), x, y)?;
+            if piece != Piece::King && board.is_fortress(x, y) {
+                return Err(HnefataflError::IsProtectedTile);
+            }
+        }
+
+        if !board.is_valid() {
+            return Err(HnefataflError::InvalidKingCount);
+        }
+        board.validate_counts(&self.rules)?;
+
+        board.set_turn(self.turn);
+        board.set_result(self.result);
+
+        Ok(board)
+    }
+}
+
+// }}}
+
+// {{{ Display
+
+impl<const N: usize> Display for Board<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Turn: {:?}", self.turn)?;
+        for row in self.board.iter() {
+            for piece in row.iter() {
+                match piece {
+                    Some(piece) => write!(f, "{}", piece)?,
+                    None => f.write_str("-")?,
+                }
+            }
+            f.write_str("\n")?;
+        }
+
+        Ok(())
+    }
+}
+
+// }}}
+
+// {{{ serde
+
+/// The parts of a board that are serialized, see the `serde` feature
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BoardData {
+    tiles: Vec<Vec<Option<Piece>>>,
+    turn: Turn,
+    result: GameResult,
+}
+
+/// Serializes the tiles row by row, the turn and the result
+///
+/// The rules and the history of the game are not included
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for Board<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BoardData {
+            tiles: self.board.iter().map(|row| row.to_vec()).collect(),
+            turn: self.turn,
+            result: self.result,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Rejects boards of the wrong size and boards without exactly one king
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for Board<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let data = BoardData::deserialize(deserializer)?;
+        if data.tiles.len() != N || data.tiles.iter().any(|row| row.len() != N) {
+            return Err(D::Error::custom(format!(
+                "board must be {} by {} tiles",
+                N, N
+            )));
+        }
+
+        let mut board = Self::blank();
+        for (y, row) in data.tiles.iter().enumerate() {
+            for (x, piece) in row.iter().enumerate() {
+                if let Some(piece) = piece {
+                    board.place_piece(*piece, x as i32, y as i32);
+                }
+            }
+        }
+        if !board.is_valid() {
+            return Err(D::Error::custom(HnefataflError::InvalidKingCount));
+        }
+        board.set_turn(data.turn);
+        board.result = data.result;
+
+        Ok(board)
+    }
+}
+
+// }}}
+
+// {{{ Iterator
+
+/// Iterator over the occupied tiles of a board, see [`Board::pieces`]
+pub struct Pieces<'a, const N: usize = 11> {
+    board: &'a Board<N>,
+    index: usize,
+}
+
+impl<const N: usize> Iterator for Pieces<'_, N> {
+    type Item = (i32, i32, Piece);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < N * N {
+            let (x, y) = ((self.index % N) as i32, (self.index / N) as i32);
+            self.index += 1;
+
+            if let Some(piece) = self.board.get_piece_unchecked(x, y) {
+                return Some((x, y, piece));
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, const N: usize> IntoIterator for &'a Board<N> {
+    type Item = (i32, i32, Piece);
+    type IntoIter = Pieces<'a, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.pieces()
+    }
+}
+
+// }}}
+
+// {{{ Index
+
+/// Get the tile at a position
+///
+/// Positions are checked against the size of the board, so this does not panic
+impl<const N: usize> Index<Position<N>> for Board<N> {
+    type Output = Option<Piece>;
+
+    fn index(&self, position: Position<N>) -> &Self::Output {
+        &self.board[position.y as usize][position.x as usize]
+    }
+}
+
+/// Change the tile at a position, like [`Board::set_piece`] without checking any rules
+///
+/// The piece on the tile is left out of the Zobrist hash until it is read, so the hash stays
+/// correct whatever is written to the tile.
+impl<const N: usize> IndexMut<Position<N>> for Board<N> {
+    fn index_mut(&mut self, position: Position<N>) -> &mut Self::Output {
+        let (x, y) = (position.x as usize, position.y as usize);
+
+        self.rehash();
+        if let Some(piece) = self.board[y][x] {
+            self.zobrist ^= Self::zobrist_key(piece, x as i32, y as i32);
+        }
+        self.unhashed = Some((x, y));
+
+        &mut self.board[y][x]
+    }
+}
+
+/// Get the tile at `(x, y)`, like [`Board::get_piece_unchecked`]
+///
+/// Panics if the coordinates are outside the board, like indexing a `Vec`
+impl<const N: usize> Index<(usize, usize)> for Board<N> {
+    type Output = Option<Piece>;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        &self.board[y][x]
+    }
+}
+
+// }}}
+
+// {{{ Default
+
+impl Default for Board<11> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// }}}
+
+// {{{ Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_codes_are_stable() {
+        use HnefataflError::*;
+
+        let errors = [
+            (NoPieceToMove, 0),
+            (PieceInTheWay, 1),
+            (StartOutOfBounds, 2),
+            (TargetOutOfBounds, 3),
+            (MoveNotHorVer, 4),
+            (WrongPieceColor, 5),
+            (IsProtectedTile, 6),
+            (TooManyCaptures, 7),
+            (GameAlreadyWon, 8),
+            (IllegalMove, 9),
+            (InvalidNotation, 10),
+            (OwnPieceAtTarget, 11),
+            (TooManyPieces, 12),
+            (PlyOutOfRange, 13),
+            (InvalidKingCount, 14),
+            (NothingToUndo, 15),
+            (InvalidBytes, 16),
+            (TileOccupied, 17),
+        ];
+
+        for (error, code) in errors {
+            assert_eq!(error.to_code(), code);
+            assert_eq!(HnefataflError::try_from(code), Ok(error));
+        }
+        assert_eq!(HnefataflError::try_from(200), Err(200));
+    }
+
+    #[test]
+    fn direction_to_from_char() {
+        use Direction::*;
+
+        for direction in [Up, Right, Down, Left] {
+            assert_eq!(Direction::try_from(direction.to_char()), Ok(direction));
+        }
+        assert_eq!(Up.to_char(), 'U');
+        assert_eq!(Left.to_char(), 'L');
+        assert_eq!(
+            Direction::try_from('u'),
+            Err(HnefataflError::InvalidNotation)
+        );
+    }
+
+    #[test]
+    fn piece_and_turn_strings() {
+        for piece in [Piece::King, Piece::Defender, Piece::Attacker] {
+            assert_eq!(piece.to_string().parse(), Ok(piece));
+            assert_eq!(Piece::try_from(char::from(piece)), Ok(piece));
+        }
+        assert_eq!(Piece::Attacker.to_string(), "A");
+        assert_eq!("KD".parse::<Piece>(), Err(HnefataflError::InvalidNotation));
+        assert_eq!("X".parse::<Piece>(), Err(HnefataflError::InvalidNotation));
+        assert_eq!("".parse::<Piece>(), Err(HnefataflError::InvalidNotation));
+
+        for turn in [Turn::White, Turn::Black] {
+            assert_eq!(turn.to_string().parse(), Ok(turn));
+        }
+        assert_eq!(Turn::White.to_string(), "White");
+        assert_eq!("b".parse(), Ok(Turn::Black));
+        assert_eq!("W".parse(), Ok(Turn::White));
+        assert_eq!("x".parse::<Turn>(), Err(HnefataflError::InvalidNotation));
+    }
+
+    #[test]
+    fn turn_to_from_u8() {
+        for turn in [Turn::White, Turn::Black] {
+            assert_eq!(Turn::try_from(turn.to_u8()), Ok(turn));
+        }
+        assert_eq!(Turn::White.to_u8(), 0);
+        assert_eq!(Turn::Black.to_u8(), 1);
+        assert_eq!(Turn::try_from(5), Err(5));
+    }
+
+    #[test]
+    fn test_board() {
+        let board = Board::new();
+
+        use Piece::*;
+
+        // {{{ A lot of asserts
+        assert_eq!(board.get_piece_unchecked(0, 0), None);
+        assert_eq!(board.get_piece_unchecked(1, 0), None);
+        assert_eq!(board.get_piece_unchecked(2, 0), None);
+        assert_eq!(board.get_piece_unchecked(3, 0), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(4, 0), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(5, 0), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(6, 0), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(7, 0), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(8, 0), None);
+        assert_eq!(board.get_piece_unchecked(9, 0), None);
+        assert_eq!(board.get_piece_unchecked(10, 0), None);
+
+        assert_eq!(board.get_piece_unchecked(0, 1), None);
+        assert_eq!(board.get_piece_unchecked(1, 1), None);
+        assert_eq!(board.get_piece_unchecked(2, 1), None);
+        assert_eq!(board.get_piece_unchecked(3, 1), None);
+        assert_eq!(board.get_piece_unchecked(4, 1), None);
+        assert_eq!(board.get_piece_unchecked(5, 1), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(6, 1), None);
+        assert_eq!(board.get_piece_unchecked(7, 1), None);
+        assert_eq!(board.get_piece_unchecked(8, 1), None);
         assert_eq!(board.get_piece_unchecked(9, 1), None);
         assert_eq!(board.get_piece_unchecked(10, 1), None);
 
-        assert_eq!(board.get_piece_unchecked(0, 2), None);
-        assert_eq!(board.get_piece_unchecked(1, 2), None);
-        assert_eq!(board.get_piece_unchecked(2, 2), None);
-        assert_eq!(board.get_piece_unchecked(3, 2), None);
-        assert_eq!(board.get_piece_unchecked(4, 2), None);
-        assert_eq!(board.get_piece_unchecked(5, 2), None);
-        assert_eq!(board.get_piece_unchecked(6, 2), None);
-        assert_eq!(board.get_piece_unchecked(7, 2), None);
-        assert_eq!(board.get_piece_unchecked(8, 2), None);
-        assert_eq!(board.get_piece_unchecked(9, 2), None);
-        assert_eq!(board.get_piece_unchecked(10, 2), None);
+        assert_eq!(board.get_piece_unchecked(0, 2), None);
+        assert_eq!(board.get_piece_unchecked(1, 2), None);
+        assert_eq!(board.get_piece_unchecked(2, 2), None);
+        assert_eq!(board.get_piece_unchecked(3, 2), None);
+        assert_eq!(board.get_piece_unchecked(4, 2), None);
+        assert_eq!(board.get_piece_unchecked(5, 2), None);
+        assert_eq!(board.get_piece_unchecked(6, 2), None);
+        assert_eq!(board.get_piece_unchecked(7, 2), None);
+        assert_eq!(board.get_piece_unchecked(8, 2), None);
+        assert_eq!(board.get_piece_unchecked(9, 2), None);
+        assert_eq!(board.get_piece_unchecked(10, 2), None);
+
+        assert_eq!(board.get_piece_unchecked(0, 3), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(1, 3), None);
+        assert_eq!(board.get_piece_unchecked(2, 3), None);
+        assert_eq!(board.get_piece_unchecked(3, 3), None);
+        assert_eq!(board.get_piece_unchecked(4, 3), None);
+        assert_eq!(board.get_piece_unchecked(5, 3), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(6, 3), None);
+        assert_eq!(board.get_piece_unchecked(7, 3), None);
+        assert_eq!(board.get_piece_unchecked(8, 3), None);
+        assert_eq!(board.get_piece_unchecked(9, 3), None);
+        assert_eq!(board.get_piece_unchecked(10, 3), Some(Attacker));
+
+        assert_eq!(board.get_piece_unchecked(0, 4), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(1, 4), None);
+        assert_eq!(board.get_piece_unchecked(2, 4), None);
+        assert_eq!(board.get_piece_unchecked(3, 4), None);
+        assert_eq!(board.get_piece_unchecked(4, 4), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(5, 4), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(6, 4), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(7, 4), None);
+        assert_eq!(board.get_piece_unchecked(8, 4), None);
+        assert_eq!(board.get_piece_unchecked(9, 4), None);
+        assert_eq!(board.get_piece_unchecked(10, 4), Some(Attacker));
+
+        assert_eq!(board.get_piece_unchecked(0, 5), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(1, 5), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(2, 5), None);
+        assert_eq!(board.get_piece_unchecked(3, 5), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(4, 5), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(5, 5), Some(King));
+        assert_eq!(board.get_piece_unchecked(6, 5), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(7, 5), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(8, 5), None);
+        assert_eq!(board.get_piece_unchecked(9, 5), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(10, 5), Some(Attacker));
+
+        assert_eq!(board.get_piece_unchecked(0, 6), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(1, 6), None);
+        assert_eq!(board.get_piece_unchecked(2, 6), None);
+        assert_eq!(board.get_piece_unchecked(3, 6), None);
+        assert_eq!(board.get_piece_unchecked(4, 6), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(5, 6), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(6, 6), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(7, 6), None);
+        assert_eq!(board.get_piece_unchecked(8, 6), None);
+        assert_eq!(board.get_piece_unchecked(9, 6), None);
+        assert_eq!(board.get_piece_unchecked(10, 6), Some(Attacker));
+
+        assert_eq!(board.get_piece_unchecked(0, 7), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(1, 7), None);
+        assert_eq!(board.get_piece_unchecked(2, 7), None);
+        assert_eq!(board.get_piece_unchecked(3, 7), None);
+        assert_eq!(board.get_piece_unchecked(4, 7), None);
+        assert_eq!(board.get_piece_unchecked(5, 7), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(6, 7), None);
+        assert_eq!(board.get_piece_unchecked(7, 7), None);
+        assert_eq!(board.get_piece_unchecked(8, 7), None);
+        assert_eq!(board.get_piece_unchecked(9, 7), None);
+        assert_eq!(board.get_piece_unchecked(10, 7), Some(Attacker));
+
+        assert_eq!(board.get_piece_unchecked(0, 8), None);
+        assert_eq!(board.get_piece_unchecked(1, 8), None);
+        assert_eq!(board.get_piece_unchecked(2, 8), None);
+        assert_eq!(board.get_piece_unchecked(3, 8), None);
+        assert_eq!(board.get_piece_unchecked(4, 8), None);
+        assert_eq!(board.get_piece_unchecked(5, 8), None);
+        assert_eq!(board.get_piece_unchecked(6, 8), None);
+        assert_eq!(board.get_piece_unchecked(7, 8), None);
+        assert_eq!(board.get_piece_unchecked(8, 8), None);
+        assert_eq!(board.get_piece_unchecked(9, 8), None);
+        assert_eq!(board.get_piece_unchecked(10, 8), None);
+
+        assert_eq!(board.get_piece_unchecked(0, 9), None);
+        assert_eq!(board.get_piece_unchecked(1, 9), None);
+        assert_eq!(board.get_piece_unchecked(2, 9), None);
+        assert_eq!(board.get_piece_unchecked(3, 9), None);
+        assert_eq!(board.get_piece_unchecked(4, 9), None);
+        assert_eq!(board.get_piece_unchecked(5, 9), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(6, 9), None);
+        assert_eq!(board.get_piece_unchecked(7, 9), None);
+        assert_eq!(board.get_piece_unchecked(8, 9), None);
+        assert_eq!(board.get_piece_unchecked(9, 9), None);
+        assert_eq!(board.get_piece_unchecked(10, 9), None);
+
+        assert_eq!(board.get_piece_unchecked(0, 10), None);
+        assert_eq!(board.get_piece_unchecked(1, 10), None);
+        assert_eq!(board.get_piece_unchecked(2, 10), None);
+        assert_eq!(board.get_piece_unchecked(3, 10), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(4, 10), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(5, 10), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(6, 10), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(7, 10), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(8, 10), None);
+        assert_eq!(board.get_piece_unchecked(9, 10), None);
+        assert_eq!(board.get_piece_unchecked(10, 10), None);
+        // }}}
+    }
+
+    #[test]
+    fn white_moves_first() {
+        let mut board = Board::new_with_first_turn(Turn::White);
+
+        assert_eq!(
+            board.move_piece(0, 7, 4, 7),
+            Err(HnefataflError::WrongPieceColor)
+        );
+        assert!(board.move_piece(5, 3, 8, 3).is_ok());
+    }
+
+    #[test]
+    fn move_onto_own_piece() {
+        let mut board = Board::new_with_first_turn(Turn::White);
+
+        assert_eq!(
+            board.move_piece(5, 3, 5, 4),
+            Err(HnefataflError::OwnPieceAtTarget)
+        );
+        assert_eq!(
+            board.move_piece(5, 3, 5, 0),
+            Err(HnefataflError::PieceInTheWay)
+        );
+        // blocked on the way to its own piece
+        assert_eq!(
+            board.move_piece(5, 3, 5, 6),
+            Err(HnefataflError::PieceInTheWay)
+        );
+        assert_eq!(
+            board.move_piece(5, 3, 5, 3),
+            Err(HnefataflError::IllegalMove)
+        );
+    }
+
+    #[test]
+    fn replay_part_of_game() {
+        let moves = [
+            Move::from(0, 7, 4, 7).unwrap().compact(),
+            Move::from(5, 3, 8, 3).unwrap().compact(),
+            Move::from(4, 7, 4, 8).unwrap().compact(),
+        ];
+
+        let mut expected = Board::new();
+        expected.move_piece(0, 7, 4, 7).unwrap();
+        expected.move_piece(5, 3, 8, 3).unwrap();
+
+        assert_eq!(Board::replay_to_ply(&moves, 2), Ok(expected));
+        assert_eq!(Board::replay_to_ply(&moves, 0), Ok(Board::new()));
+        assert_eq!(
+            Board::replay_to_ply(&moves, 4),
+            Err(HnefataflError::PlyOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_move_unchecked() {
+        let mut board = Board::new();
+
+        board.move_piece_uncheced(0, 7, 5, 7);
+
+        assert_eq!(board.get_piece_unchecked(0, 7), None);
+        assert_eq!(board.get_piece_unchecked(5, 7), Some(Piece::Attacker));
+    }
+
+    #[test]
+    fn occupied_and_empty_tiles() {
+        let board = Board::new();
+
+        assert!(board.occupied(5, 5));
+        assert!(!board.is_empty(5, 5));
+
+        assert!(!board.occupied(0, 0));
+        assert!(board.is_empty(0, 0));
+
+        // out of bounds tiles are neither
+        for (x, y) in [(-1, 0), (0, 11), (11, 5), (3, -2)] {
+            assert!(!board.occupied(x, y));
+            assert!(!board.is_empty(x, y));
+        }
+    }
+
+    #[test]
+    fn count_piece_kinds() {
+        let board = Board::new();
+
+        assert_eq!(board.defenders().count(), 12);
+        assert_eq!(board.attackers().count(), 24);
+        assert_eq!(board.kings().collect::<Vec<_>>(), vec![(5, 5)]);
+        assert!(board
+            .attackers()
+            .all(|(x, y)| board.get_piece_unchecked(x, y) == Some(Piece::Attacker)));
+    }
+
+    #[test]
+    fn pieces_of_each_side() {
+        let board = Board::new();
+        assert_eq!(board.pieces().count(), 37);
+
+        let white = board.pieces_of(Turn::White).collect::<Vec<_>>();
+        assert_eq!(white.len(), 13);
+        assert!(white.contains(&(5, 5, Piece::King)));
+        assert!(white.contains(&(5, 3, Piece::Defender)));
+        assert!(white.contains(&(7, 5, Piece::Defender)));
+
+        let black = board.pieces_of(Turn::Black).collect::<Vec<_>>();
+        assert_eq!(black.len(), 24);
+        assert!(black.iter().all(|(_, _, p)| *p == Piece::Attacker));
+        assert!(black.contains(&(3, 0, Piece::Attacker)));
+        assert!(black.contains(&(10, 5, Piece::Attacker)));
+    }
+
+    #[test]
+    fn king_position() {
+        let mut board = Board::new();
+        assert_eq!(board.king_position(), Some((5, 5)));
+        assert_eq!(board.king_distance_to_nearest_corner(), Some(10));
+
+        board.move_piece_uncheced(5, 5, 9, 2);
+        assert_eq!(board.king_position(), Some((9, 2)));
+        assert_eq!(board.king_distance_to_nearest_corner(), Some(3));
+
+        board.remove_piece(9, 2);
+        assert_eq!(board.king_position(), None);
+        assert_eq!(board.king_distance_to_nearest_corner(), None);
+    }
+
+    #[test]
+    fn positions() {
+        assert!(StandardBoard::position(0, 0).is_some());
+        assert!(StandardBoard::position(10, 10).is_some());
+        assert_eq!(StandardBoard::position(11, 0), None);
+        assert_eq!(StandardBoard::position(0, 11), None);
+        assert_eq!(StandardBoard::position(-1, 5), None);
+        assert_eq!(StandardBoard::position(5, -1), None);
+
+        let position = StandardBoard::position(3, 0).unwrap();
+        assert_eq!((position.x(), position.y()), (3, 0));
+        assert_eq!(<(i32, i32)>::from(position), (3, 0));
+        assert_eq!(Position::try_from((3, 0)), Ok(position));
+        assert_eq!(Position::<11>::try_from((3, 11)), Err((3, 11)));
+
+        let board = Board::new();
+        assert_eq!(board[position], Some(Piece::Attacker));
+        assert_eq!(
+            board[StandardBoard::position(5, 5).unwrap()],
+            Some(Piece::King)
+        );
+        assert_eq!(board[StandardBoard::position(0, 0).unwrap()], None);
+
+        // positions are checked against the size of the board
+        assert!(Board::<7>::position(6, 6).is_some());
+        assert_eq!(Board::<7>::position(7, 0), None);
+        assert_eq!(Board::<7>::position(9, 9), None);
+        assert_eq!(Position::<7>::try_from((3, 7)), Err((3, 7)));
+    }
+
+    #[test]
+    fn index_mut_keeps_hash() {
+        let mut board = Board::new();
+        let mut expected = Board::new();
+
+        let position = StandardBoard::position(3, 0).unwrap();
+        board[position] = Some(Piece::Defender);
+        expected.set_piece(Some(Piece::Defender), 3, 0).unwrap();
+        assert_eq!(board[position], Some(Piece::Defender));
+        assert_eq!(board, expected);
+        assert_eq!(board.zobrist(), expected.zobrist());
+
+        // changing the tile again, and then placing pieces, still hashes every piece
+        board[position] = None;
+        board.move_piece_uncheced(5, 5, 3, 0);
+        expected.move_piece_uncheced(5, 5, 3, 0);
+        assert_eq!(board.zobrist(), expected.zobrist());
+
+        let mut small = Board::<7>::brandub();
+        let center = Board::<7>::position(3, 3).unwrap();
+        small[center] = None;
+        assert_eq!(small[center], None);
+        assert_eq!(small.king_position(), None);
+    }
+
+    #[test]
+    fn index_by_coordinates() {
+        let board = Board::new();
+
+        for (x, y) in [(5, 5), (3, 0), (0, 3), (5, 3), (7, 5), (0, 0), (2, 2)] {
+            assert_eq!(board[(x, y)], board.get_piece_unchecked(x as i32, y as i32));
+        }
+        assert_eq!(board[(5, 5)], Some(Piece::King));
+        assert_eq!(board[(10, 4)], Some(Piece::Attacker));
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds() {
+        let _ = Board::new()[(11, 0)];
+    }
+
+    #[test]
+    fn set_pieces() {
+        let mut board = Board::empty();
+
+        board.set_piece(Some(Piece::King), 5, 5).unwrap();
+        board.set_piece(Some(Piece::Attacker), 10, 3).unwrap();
+        board.set_piece(Some(Piece::Defender), 0, 7).unwrap();
+        assert_eq!(board[(10, 3)], Some(Piece::Attacker));
+        assert_eq!(board.pieces().count(), 3);
+
+        board.set_piece(Some(Piece::Defender), 10, 3).unwrap();
+        assert_eq!(board[(10, 3)], Some(Piece::Defender));
+        board.set_piece(None, 10, 3).unwrap();
+        assert_eq!(board[(10, 3)], None);
+
+        let mut expected = Board::empty();
+        expected.place_piece(Piece::King, 5, 5);
+        expected.place_piece(Piece::Defender, 0, 7);
+        assert_eq!(board, expected);
+        assert_eq!(board.zobrist(), expected.zobrist());
+
+        for (x, y) in [(11, 0), (0, 11), (-1, 0), (0, -1)] {
+            assert_eq!(
+                board.set_piece(Some(Piece::Attacker), x, y),
+                Err(HnefataflError::TargetOutOfBounds)
+            );
+        }
+        assert_eq!(
+            Board::<7>::blank().set_piece(None, 7, 0),
+            Err(HnefataflError::TargetOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn build_board() {
+        let board = BoardBuilder::new()
+            .piece(Piece::King, 2, 2)
+            .piece(Piece::Defender, 2, 3)
+            .piece(Piece::Attacker, 6, 2)
+            .turn(Turn::White)
+            .build()
+            .unwrap();
+
+        let mut expected = Board::empty();
+        expected.place_piece(Piece::King, 2, 2);
+        expected.place_piece(Piece::Defender, 2, 3);
+        expected.place_piece(Piece::Attacker, 6, 2);
+        expected.set_turn(Turn::White);
+        assert_eq!(board, expected);
+
+        let won = BoardBuilder::<7>::new()
+            .piece(Piece::King, 0, 0)
+            .result(GameResult::DefenderWin)
+            .build()
+            .unwrap();
+        assert_eq!(won.winner(), Some(Turn::White));
+        assert_eq!(won.get_turn(), Turn::Black);
+    }
+
+    #[test]
+    fn build_invalid_boards() {
+        let king = BoardBuilder::<11>::new().piece(Piece::King, 5, 5);
+
+        assert_eq!(
+            king.clone().piece(Piece::King, 2, 2).build(),
+            Err(HnefataflError::InvalidKingCount)
+        );
+        assert_eq!(
+            BoardBuilder::<11>::new().build(),
+            Err(HnefataflError::InvalidKingCount)
+        );
+        assert_eq!(
+            king.clone().piece(Piece::Attacker, 5, 5).build(),
+            Err(HnefataflError::TileOccupied)
+        );
+        assert_eq!(
+            king.clone().piece(Piece::Attacker, 11, 5).build(),
+            Err(HnefataflError::TargetOutOfBounds)
+        );
+        assert_eq!(
+            king.clone().piece(Piece::Defender, 0, 10).build(),
+            Err(HnefataflError::IsProtectedTile)
+        );
+        assert_eq!(
+            king.rules(RuleSet {
+                max_attackers: 0,
+                ..Default::default()
+            })
+            .piece(Piece::Attacker, 1, 1)
+            .build(),
+            Err(HnefataflError::TooManyPieces)
+        );
+    }
+
+    #[test]
+    fn board_as_chars() {
+        let mut board = Board::new();
+        board.move_piece_uncheced(5, 5, 2, 2);
+
+        let chars = board.as_2d_chars();
+
+        assert_eq!(chars.len(), 11);
+        assert!(chars.iter().all(|row| row.len() == 11));
+
+        assert_eq!(chars[0][0], '#');
+        assert_eq!(chars[10][10], '#');
+        assert_eq!(chars[5][5], '+');
+        assert_eq!(chars[2][2], 'K');
+        assert_eq!(chars[0][3], 'A');
+        assert_eq!(chars[3][5], 'D');
+        assert_eq!(chars[1][1], '.');
+    }
+
+    #[test]
+    fn iterate_over_pieces() {
+        let board = Board::new();
+
+        let mut count = 0;
+        for (x, y, piece) in &board {
+            assert_eq!(board.get_piece_unchecked(x, y), Some(piece));
+            count += 1;
+        }
+
+        assert_eq!(count, 37);
+    }
+
+    #[test]
+    fn ray_stops_at_blocking_piece() {
+        let board = Board::new();
+
+        assert_eq!(
+            board.ray(5, 1, Direction::Down),
+            vec![((5, 2), None), ((5, 3), Some(Piece::Defender))]
+        );
+        assert_eq!(
+            board.ray(8, 0, Direction::Right),
+            vec![((9, 0), None), ((10, 0), None)]
+        );
+    }
+
+    #[test]
+    fn king_on_throne() {
+        let mut board = Board::new();
+        assert!(board.is_king_on_throne());
+
+        board.move_piece_uncheced(5, 4, 8, 2);
+        board.set_turn(Turn::White);
+        board.move_piece(5, 5, 5, 4).unwrap();
+        assert!(!board.is_king_on_throne());
+    }
+
+    #[test]
+    fn board_needs_one_king() {
+        let mut board = Board::new();
+        assert!(board.is_valid());
+
+        board.place_piece(Piece::King, 0, 5);
+        assert!(!board.is_valid());
+
+        assert!(!Board::empty().is_valid());
+    }
+
+    #[test]
+    fn validate_piece_counts() {
+        let rules = RuleSet::default();
+
+        let mut board = Board::new();
+        assert_eq!(board.validate_counts(&rules), Ok(()));
+
+        // pieces captured during the game
+        board.remove_piece(5, 3);
+        board.remove_piece(0, 3);
+        board.remove_piece(5, 5);
+        assert_eq!(board.validate_counts(&rules), Ok(()));
+
+        board.place_piece(Piece::Attacker, 2, 2);
+        board.place_piece(Piece::Attacker, 2, 3);
+        assert_eq!(
+            board.validate_counts(&rules),
+            Err(HnefataflError::TooManyPieces)
+        );
+
+        let mut board = Board::new();
+        board.place_piece(Piece::King, 2, 2);
+        assert_eq!(
+            board.validate_counts(&rules),
+            Err(HnefataflError::TooManyPieces)
+        );
+    }
+
+    #[test]
+    fn planes_round_trip() {
+        let mut board = Board::new();
+        board.move_piece(3, 0, 3, 2).unwrap();
+
+        let planes = board.to_planes();
+        assert_eq!(planes[0].iter().filter(|&&bit| bit == 1).count(), 1);
+        assert_eq!(planes[3], [Turn::White.to_u8(); 121]);
+
+        let restored = Board::from_planes(&planes).unwrap();
+        assert!(restored.same_position(&board));
+        assert_eq!(restored.get_turn(), board.get_turn());
+    }
+
+    #[test]
+    fn planes_need_one_king() {
+        let mut planes = Board::new().to_planes();
+        planes[0] = [0; 121];
+        assert_eq!(
+            Board::from_planes(&planes).err(),
+            Some(HnefataflError::InvalidKingCount)
+        );
+
+        planes[0][0] = 1;
+        planes[0][10] = 1;
+        assert_eq!(
+            Board::from_planes(&planes).err(),
+            Some(HnefataflError::InvalidKingCount)
+        );
+    }
+
+    #[test]
+    fn planes_must_not_overlap() {
+        let mut planes = Board::new().to_planes();
+        // an attacker on the tile of a defender
+        planes[2][4 * 11 + 5] = 1;
+        assert_eq!(
+            Board::from_planes(&planes).err(),
+            Some(HnefataflError::TileOccupied)
+        );
+
+        let mut planes = Board::new().to_planes();
+        planes[1][5 * 11 + 5] = 1;
+        assert_eq!(
+            Board::from_planes(&planes).err(),
+            Some(HnefataflError::TileOccupied)
+        );
+    }
+
+    #[test]
+    fn move_to_compact() {
+        let board = Board::new();
+
+        let compact = board.move_to_compact((3, 0), (3, 2)).unwrap();
+        let m: Move = compact.into();
+        assert_eq!(m, Move::from(3, 0, 3, 2).unwrap());
+        assert_eq!(compact.capture_count(), 0);
+
+        assert_eq!(
+            board.move_to_compact((3, 0), (4, 2)),
+            Err(HnefataflError::MoveNotHorVer)
+        );
+        assert_eq!(
+            board.move_to_compact((3, 0), (3, 11)),
+            Err(HnefataflError::TargetOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn first_blocker() {
+        let board = Board::new();
+
+        assert_eq!(board.first_blocker(5, 5, Direction::Up), Some((5, 4)));
+        assert_eq!(board.first_blocker(3, 0, Direction::Down), Some((3, 5)));
+        assert_eq!(board.first_blocker(5, 1, Direction::Right), None);
+        assert_eq!(board.first_blocker(0, 0, Direction::Left), None);
+    }
+
+    #[test]
+    fn quiet_positions() {
+        assert!(Board::new().is_quiet());
+
+        let mut board = Board::empty();
+        board.place_piece(Piece::Attacker, 2, 3);
+        board.place_piece(Piece::Defender, 3, 3);
+        board.place_piece(Piece::Attacker, 5, 3);
+        board.place_piece(Piece::King, 8, 8);
+        board.set_turn(Turn::Black);
+        assert!(!board.is_quiet());
+
+        board.set_turn(Turn::White);
+        assert!(board.is_quiet());
+    }
+
+    #[test]
+    fn describe_escape() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 0, 3);
+        board.set_turn(Turn::White);
+        board.ply = 44;
+        assert_eq!(board.describe_outcome(), None);
+
+        board.move_piece(0, 3, 0, 0).unwrap();
+        assert_eq!(
+            board.describe_outcome().as_deref(),
+            Some("White wins: king escaped to the northwest corner on move 23")
+        );
+    }
+
+    #[test]
+    fn describe_capture() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 4, 5);
+        board.place_piece(Piece::Attacker, 4, 4);
+        board.place_piece(Piece::Attacker, 4, 6);
+        board.place_piece(Piece::Attacker, 1, 5);
+        board.set_turn(Turn::Black);
+
+        board.move_piece(1, 5, 3, 5).unwrap();
+        assert_eq!(
+            board.describe_outcome().as_deref(),
+            Some("Black wins: king captured on move 1")
+        );
+    }
+
+    #[test]
+    fn apply_returns_captures() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 9, 9);
+        board.place_piece(Piece::Attacker, 5, 7);
+        board.place_piece(Piece::Defender, 4, 3);
+        board.place_piece(Piece::Attacker, 3, 3);
+        board.place_piece(Piece::Defender, 6, 3);
+        board.place_piece(Piece::Attacker, 7, 3);
+        board.place_piece(Piece::Defender, 5, 2);
+        board.place_piece(Piece::Attacker, 5, 1);
+
+        let mut captured = board.apply(&Move::from(5, 7, 5, 3).unwrap()).unwrap();
+        captured.sort_by_key(|&(x, y, _)| (x, y));
+        assert_eq!(
+            captured,
+            vec![
+                (4, 3, Piece::Defender),
+                (5, 2, Piece::Defender),
+                (6, 3, Piece::Defender)
+            ]
+        );
+        assert_eq!(board.defenders().count(), 0);
+
+        let captured = board.apply(&Move::from(9, 9, 9, 8).unwrap()).unwrap();
+        assert!(captured.is_empty());
+        assert!(board.apply(&Move::from(9, 8, 9, 7).unwrap()).is_err());
+    }
+
+    #[test]
+    fn undo_capture() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::Attacker, 3, 3);
+        board.place_piece(Piece::Attacker, 5, 7);
+        board.place_piece(Piece::Defender, 4, 3);
+        board.place_piece(Piece::King, 8, 8);
+        let before = board.clone();
+
+        assert_eq!(board.undo(), Err(HnefataflError::NothingToUndo));
+
+        let m = board.move_piece(5, 7, 5, 3).unwrap();
+        assert_eq!(m.capture_count(), 1);
+
+        board.undo().unwrap();
+        assert_eq!(board, before);
+        assert_eq!(board.checkpoint(), before.checkpoint());
+        assert_eq!(board.stats(), before.stats());
+    }
+
+    #[test]
+    fn undo_win() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 0, 4);
+        board.place_piece(Piece::Attacker, 8, 8);
+        board.set_turn(Turn::White);
+        let before = board.clone();
+
+        board.move_piece(0, 4, 0, 0).unwrap();
+        assert!(board.is_game_over());
+
+        board.undo().unwrap();
+        assert_eq!(board, before);
+        assert_eq!(board.result(), GameResult::Ongoing);
+        assert!(board.move_piece(0, 4, 0, 2).is_ok());
+    }
+
+    #[test]
+    fn brandub_board() {
+        let board = Board::brandub();
+
+        #[rustfmt::skip]
+        let layout = [
+            "...A...",
+            "...A...",
+            "...D...",
+            "AADKDAA",
+            "...D...",
+            "...A...",
+            "...A...",
+        ];
+        for (y, row) in layout.iter().enumerate() {
+            for (x, tile) in row.chars().enumerate() {
+                let piece = Piece::try_from(tile).ok();
+                assert_eq!(board.get_piece_unchecked(x as i32, y as i32), piece);
+            }
+        }
+
+        assert!(board.is_valid());
+        assert!(board.validate_counts(board.get_rules()).is_ok());
+        for (x, y) in [(0, 0), (6, 0), (0, 6), (6, 6), (3, 3)] {
+            assert!(board.is_fortress(x, y));
+        }
+        assert_eq!(board.get_turn(), Turn::Black);
+    }
+
+    #[test]
+    fn tablut_board() {
+        let board = Board::tablut();
+
+        assert_eq!(board.attackers().count(), 16);
+        assert_eq!(board.defenders().count(), 8);
+        assert_eq!(board.kings().collect::<Vec<_>>(), vec![(4, 4)]);
+        assert!(board.validate_counts(board.get_rules()).is_ok());
+
+        use Piece::*;
+        assert_eq!(board.get_piece_unchecked(4, 0), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(4, 1), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(8, 5), Some(Attacker));
+        assert_eq!(board.get_piece_unchecked(4, 2), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(6, 4), Some(Defender));
+        assert_eq!(board.get_piece_unchecked(2, 0), None);
+        assert_eq!(board.get_piece_unchecked(3, 3), None);
+
+        for (x, y) in [(0, 0), (8, 0), (0, 8), (8, 8), (4, 4)] {
+            assert!(board.is_fortress(x, y));
+        }
+    }
+
+    #[test]
+    fn piece_limits_follow_board_size() {
+        assert_eq!(Board::<9>::blank().get_rules(), Board::tablut().get_rules());
+        assert_eq!(
+            Board::<7>::blank().get_rules(),
+            Board::brandub().get_rules()
+        );
+        assert_eq!(Board::empty().get_rules(), &RuleSet::default());
+
+        let mut builder = BoardBuilder::<7>::new().piece(Piece::King, 3, 3);
+        for x in 1..=5 {
+            builder = builder
+                .piece(Piece::Attacker, x, 0)
+                .piece(Piece::Attacker, x, 6);
+        }
+        assert_eq!(builder.build().unwrap_err(), HnefataflError::TooManyPieces);
+    }
+
+    #[test]
+    fn small_board_bounds() {
+        let mut board = Board::<7>::blank();
+        assert_eq!(board.as_2d_chars().len(), 7);
+        assert!(board.is_corner(6, 6));
+        assert!(!board.is_corner(10, 10));
+        assert!(board.is_throne(3, 3));
+        assert!(!board.is_throne(5, 5));
+
+        board.place_piece(Piece::Attacker, 6, 2);
+        assert_eq!(board.get_piece_checked(7, 2), None);
+        assert_eq!(board.first_blocker(0, 2, Direction::Right), Some((6, 2)));
+        assert_eq!(
+            board.move_piece(6, 2, 7, 2),
+            Err(HnefataflError::TargetOutOfBounds)
+        );
+
+        // the corners stop the piece at (6,1) and (6,5)
+        assert_eq!(board.moves_from(6, 2).len(), 10);
+        assert!(board.move_piece(6, 2, 6, 5).is_ok());
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let board = Board::new();
+        let bytes = board.to_bytes();
+        assert_eq!(bytes.len(), 33);
+        assert_eq!(StandardBoard::from_bytes(&bytes), Ok(board));
+
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 0, 10);
+        board.place_piece(Piece::Defender, 10, 10);
+        board.place_piece(Piece::Attacker, 3, 7);
+        board.set_turn(Turn::Black);
+        assert_eq!(
+            StandardBoard::from_bytes(&board.to_bytes()),
+            Ok(board.clone())
+        );
+
+        board.set_result(GameResult::DefenderWin);
+        assert_eq!(StandardBoard::from_bytes(&board.to_bytes()), Ok(board));
+
+        let board = Board::brandub();
+        let restored = Board::<7>::from_bytes(&board.to_bytes()).unwrap();
+        assert!(restored.same_position(&board));
+        assert_eq!(restored.get_turn(), board.get_turn());
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        let mut bytes = Board::new().to_bytes();
+        assert_eq!(
+            StandardBoard::from_bytes(&bytes[..32]),
+            Err(HnefataflError::InvalidBytes)
+        );
+
+        bytes[31] = 2;
+        assert_eq!(
+            StandardBoard::from_bytes(&bytes),
+            Err(HnefataflError::InvalidBytes)
+        );
+        bytes[31] = 0;
+        bytes[32] = 4;
+        assert_eq!(
+            StandardBoard::from_bytes(&bytes),
+            Err(HnefataflError::InvalidBytes)
+        );
+
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 0, 10);
+        board.place_piece(Piece::King, 10, 10);
+        assert_eq!(
+            StandardBoard::from_bytes(&board.to_bytes()),
+            Err(HnefataflError::InvalidKingCount)
+        );
+    }
+
+    #[test]
+    fn notation_round_trip() {
+        let board = Board::new();
+        let notation = board.to_notation();
+        assert_eq!(
+            notation,
+            "3AAAAA3/5A5/11/A4D4A/A3DDD3A/AA1DDKDD1AA/A3DDD3A/A4D4A/11/5A5/3AAAAA3 b"
+        );
+        assert_eq!(StandardBoard::from_notation(&notation), Ok(board));
+
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 10, 0);
+        board.place_piece(Piece::Defender, 0, 10);
+        board.place_piece(Piece::Attacker, 5, 4);
+        board.set_turn(Turn::White);
+        assert_eq!(
+            StandardBoard::from_notation(&board.to_notation()),
+            Ok(board)
+        );
+
+        let board = Board::brandub();
+        let restored = Board::<7>::from_notation(&board.to_notation()).unwrap();
+        assert!(restored.same_position(&board));
+    }
+
+    #[test]
+    fn invalid_notation() {
+        for notation in [
+            // a row that is too short
+            "3AAAAA3/5A5/11/A4D4A/A3DDD3A/AA1DDKDD1AA/A3DDD3A/A4D3A/11/5A5/3AAAAA3 b",
+            // a row that is too long
+            "3AAAAA3/5A5/11/A4D4A/A3DDD3A/AA1DDKDD1AA/A3DDD3A/A4D5A/11/5A5/3AAAAA3 b",
+            "3AAAAA3/5A5/12/A4D4A/A3DDD3A/AA1DDKDD1AA/A3DDD3A/A4D4A/11/5A5/3AAAAA3 b",
+            // a missing row
+            "3AAAAA3/5A5/A4D4A/A3DDD3A/AA1DDKDD1AA/A3DDD3A/A4D4A/11/5A5/3AAAAA3 b",
+            // an unknown piece
+            "3AAAAA3/5A5/11/A4D4A/A3DDD3A/AA1DDKDD1AA/A3DDD3A/A4X4A/11/5A5/3AAAAA3 b",
+            // bad side to move
+            "3AAAAA3/5A5/11/A4D4A/A3DDD3A/AA1DDKDD1AA/A3DDD3A/A4D4A/11/5A5/3AAAAA3 x",
+            "3AAAAA3/5A5/11/A4D4A/A3DDD3A/AA1DDKDD1AA/A3DDD3A/A4D4A/11/5A5/3AAAAA3 bw",
+            "3AAAAA3/5A5/11/A4D4A/A3DDD3A/AA1DDKDD1AA/A3DDD3A/A4D4A/11/5A5/3AAAAA3",
+        ] {
+            assert_eq!(
+                StandardBoard::from_notation(notation),
+                Err(HnefataflError::InvalidNotation),
+                "{}",
+                notation
+            );
+        }
+
+        assert_eq!(
+            StandardBoard::from_notation("K10/11/11/11/11/11/11/11/11/11/10K w"),
+            Err(HnefataflError::InvalidKingCount)
+        );
+    }
+
+    #[test]
+    fn hash_without_turn() {
+        let black = Board::new();
+        let white = Board::new_with_first_turn(Turn::White);
+        assert_ne!(black.zobrist(), white.zobrist());
+        assert_eq!(black.hash_without_turn(), white.hash_without_turn());
+
+        let mut moved = Board::new();
+        moved.move_piece(3, 0, 3, 2).unwrap();
+        assert_ne!(moved.hash_without_turn(), white.hash_without_turn());
+    }
+
+    #[test]
+    fn zobrist_hash() {
+        let mut board = Board::new();
+        let start = board.zobrist();
+        assert_ne!(start, 0);
+
+        board.move_piece(3, 0, 3, 2).unwrap();
+        assert_ne!(board.zobrist(), start);
+        board.undo().unwrap();
+        assert_eq!(board.zobrist(), start);
+
+        // a capture takes a piece off the board, undo puts it back
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 0, 10);
+        board.place_piece(Piece::Attacker, 3, 4);
+        board.place_piece(Piece::Defender, 4, 4);
+        board.place_piece(Piece::Attacker, 6, 2);
+        let before = board.zobrist();
+        board.move_piece(6, 2, 5, 2).unwrap();
+        board.move_piece(0, 10, 0, 9).unwrap();
+        board.move_piece(5, 2, 5, 4).unwrap();
+        assert_eq!(board.get_piece_unchecked(4, 4), None);
+        board.undo().unwrap();
+        board.undo().unwrap();
+        board.undo().unwrap();
+        assert_eq!(board.zobrist(), before);
+
+        // the same position reached in different ways hashes the same
+        let mut other = Board::new();
+        other.move_piece(3, 0, 3, 2).unwrap();
+        other.move_piece(5, 3, 2, 3).unwrap();
+        let mut board = Board::new();
+        board.move_piece(3, 0, 3, 1).unwrap();
+        board.move_piece(5, 3, 2, 3).unwrap();
+        board.move_piece(3, 1, 3, 2).unwrap();
+        board.pass().unwrap();
+        assert_eq!(board, other);
+        assert_eq!(board.zobrist(), other.zobrist());
+
+        let from_notation = StandardBoard::from_notation(&board.to_notation()).unwrap();
+        assert_eq!(from_notation.zobrist(), board.zobrist());
+
+        board.set_turn(Turn::White);
+        assert_ne!(board.zobrist(), other.zobrist());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut board = Board::new();
+        board.move_piece(3, 0, 3, 2).unwrap();
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(serde_json::from_str::<StandardBoard>(&json).unwrap(), board);
+
+        let m = board
+            .do_move(&Move::from(5, 3, 2, 3).unwrap())
+            .unwrap()
+            .into();
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(serde_json::from_str::<Move>(&json).unwrap(), m);
+
+        // a board of the wrong size
+        let json = serde_json::to_string(&Board::brandub()).unwrap();
+        assert!(serde_json::from_str::<StandardBoard>(&json).is_err());
+
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 0, 10);
+        board.place_piece(Piece::King, 10, 10);
+        let json = serde_json::to_string(&board).unwrap();
+        assert!(serde_json::from_str::<StandardBoard>(&json).is_err());
+    }
+
+    #[test]
+    fn history_capacity() {
+        let mut board = Board::with_capacity_history(300);
+        assert!(board.history.capacity() >= 300);
+        assert!(board.positions.capacity() >= 300);
+        assert_eq!(board, Board::new());
+
+        board.do_move(&Move::from(3, 0, 3, 2).unwrap()).unwrap();
+        board.reserve_history(500);
+        assert!(board.history.capacity() >= 501);
+    }
+
+    #[test]
+    fn restore_checkpoint() {
+        let mut board = Board::new();
+        board.move_piece(0, 7, 4, 7).unwrap();
+
+        let saved = board.clone();
+        let checkpoint = board.checkpoint();
+
+        board.move_piece(5, 3, 8, 3).unwrap();
+        board.move_piece(4, 7, 4, 8).unwrap();
+        board.move_piece(3, 5, 3, 7).unwrap();
+        assert_ne!(board, saved);
+
+        board.restore(&checkpoint);
+        assert_eq!(board, saved);
+
+        board.undo().unwrap();
+        assert_eq!(board, Board::new());
+    }
+
+    #[test]
+    fn undo_after_restoring_later_checkpoint() {
+        let mut board = Board::new();
+        board.move_piece(0, 7, 4, 7).unwrap();
+        board.move_piece(5, 3, 8, 3).unwrap();
+        let later = board.checkpoint();
+
+        // back to before the checkpoint, and on with another move
+        board.undo().unwrap();
+        board.move_piece(7, 5, 7, 8).unwrap();
+        board.restore(&later);
+
+        let restored = board.clone();
+        assert_eq!(board.undo(), Err(HnefataflError::NothingToUndo));
+        assert_eq!(board, restored);
+        assert_eq!(board.get_piece_unchecked(8, 3), Some(Piece::Defender));
+
+        // a checkpoint from further ahead, without a different move in between
+        let mut board = Board::new();
+        board.move_piece(0, 7, 4, 7).unwrap();
+        board.move_piece(5, 3, 8, 3).unwrap();
+        let later = board.checkpoint();
+        board.undo().unwrap();
+        board.restore(&later);
+        assert_eq!(board.undo(), Err(HnefataflError::NothingToUndo));
+    }
+
+    #[test]
+    fn stats_mid_game() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 9, 9);
+        board.place_piece(Piece::Attacker, 3, 3);
+        board.place_piece(Piece::Attacker, 5, 7);
+        board.place_piece(Piece::Defender, 4, 3);
+        board.place_piece(Piece::Defender, 8, 8);
+
+        board.move_piece(5, 7, 5, 3).unwrap();
+        board.move_piece(8, 8, 8, 3).unwrap();
+
+        assert_eq!(
+            board.stats(),
+            GameStats {
+                ply: 2,
+                legal_moves: board.available_moves().len(),
+                white_captured: 1,
+                black_captured: 0,
+            }
+        );
+        assert_eq!(board.stats().legal_moves, 21);
+    }
+
+    #[test]
+    fn same_position_ignores_turn() {
+        let board = Board::new();
+        let mut other = Board::new();
+        other.set_turn(Turn::White);
+
+        assert!(board.same_position(&other));
+        assert_ne!(board, other);
+    }
+
+    #[test]
+    fn pass_twice_is_identity() {
+        let mut board = Board::new();
+
+        board.pass().unwrap();
+        assert_eq!(board.get_turn(), Turn::White);
+
+        board.pass().unwrap();
+        assert_eq!(board, Board::new());
+
+        board.set_result(GameResult::AttackerWin);
+        assert_eq!(board.pass(), Err(HnefataflError::GameAlreadyWon));
+    }
+
+    #[test]
+    fn is_legal_does_not_move() {
+        let board = Board::new();
+        let before = board.clone();
+
+        for (x, y, to_x, to_y) in [
+            (1, 7, 4, 7),
+            (0, 3, 0, 0),
+            (0, 7, 5, 7),
+            (3, 5, 2, 5),
+            (3, 0, 7, 0),
+        ] {
+            assert!(!board.is_legal(&Move::from(x, y, to_x, to_y).unwrap()));
+        }
+
+        // moves that can not even be made into a `Move` are checked by `validate`
+        assert_eq!(
+            board.validate(0, 7, 3, 9),
+            Err(HnefataflError::MoveNotHorVer)
+        );
+        assert_eq!(
+            board.validate(0, 7, 0, 11),
+            Err(HnefataflError::TargetOutOfBounds)
+        );
+        assert_eq!(
+            board.validate(-4, 7, 3, 7),
+            Err(HnefataflError::StartOutOfBounds)
+        );
+
+        assert!(board.is_legal(&Move::from(0, 7, 4, 7).unwrap()));
+        assert_eq!(board, before);
+        assert_eq!(board.checkpoint(), before.checkpoint());
+    }
+
+    #[test]
+    fn test_move() {
+        let mut board = Board::new();
+
+        // {{{ Errors on move
+        assert_eq!(
+            board.move_piece(1, 7, 4, 7),
+            Err(HnefataflError::NoPieceToMove)
+        );
+
+        assert_eq!(
+            board.move_piece(0, 3, 0, 0),
+            Err(HnefataflError::IsProtectedTile)
+        );
+
+        assert_eq!(
+            board.move_piece(0, 7, 3, 9),
+            Err(HnefataflError::MoveNotHorVer)
+        );
+
+        assert_eq!(
+            board.move_piece(0, 7, -2, 7),
+            Err(HnefataflError::TargetOutOfBounds)
+        );
+
+        assert_eq!(
+            board.move_piece(0, 7, 0, 11),
+            Err(HnefataflError::TargetOutOfBounds)
+        );
+
+        assert_eq!(
+            board.move_piece(-4, 7, 3, 7),
+            Err(HnefataflError::StartOutOfBounds)
+        );
+
+        assert_eq!(
+            board.move_piece(0, 19, 0, 7),
+            Err(HnefataflError::StartOutOfBounds)
+        );
+
+        assert_eq!(
+            board.move_piece(0, 7, 5, 7),
+            Err(HnefataflError::PieceInTheWay)
+        );
+
+        assert_eq!(
+            board.move_piece(3, 5, 2, 5),
+            Err(HnefataflError::WrongPieceColor)
+        );
+
+        assert_eq!(
+            board.move_piece(3, 0, 7, 0),
+            Err(HnefataflError::PieceInTheWay)
+        );
+        // }}}
+
+        assert_eq!(
+            board.move_piece(0, 7, 4, 7),
+            Move::from(0, 7, 4, 7).map(|m| m.compact())
+        );
+        assert_eq!(board.get_piece_unchecked(0, 7), None);
+        assert_eq!(board.get_piece_unchecked(4, 7), Some(Piece::Attacker));
+    }
+
+    #[test]
+    fn move_destination() {
+        let m = Move::from(0, 7, 4, 7).unwrap();
+        assert_eq!(m.destination(), (4, 7));
+        assert!(m.is_in_bounds(11));
+
+        let m = Move::from(3, 10, 3, 12).unwrap();
+        assert_eq!(m.destination(), (3, 12));
+        assert!(!m.is_in_bounds(11));
+    }
+
+    #[test]
+    fn same_path_ignores_captures() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 4, 5);
+        board.place_piece(Piece::Attacker, 4, 4);
+        board.place_piece(Piece::Attacker, 4, 6);
+        board.place_piece(Piece::Attacker, 1, 5);
+
+        let user_move = Move::from(1, 5, 3, 5).unwrap();
+        let performed: Move = board.do_move(&user_move).unwrap().into();
+
+        assert_ne!(user_move, performed);
+        assert!(user_move.same_path(&performed));
+        assert!(!user_move.same_path(&Move::from(1, 5, 2, 5).unwrap()));
+    }
+
+    #[test]
+    fn compact_move_as_u32() {
+        let mut m = Move::from(3, 9, 3, 2).unwrap();
+        m.add_capture(Direction::Left).unwrap();
+        let compact = m.compact();
+
+        assert_eq!(CompactMove::from_u32(compact.as_u32()), compact);
+        let decoded: Move = CompactMove::from_u32(compact.as_u32()).into();
+        assert_eq!(decoded, m);
+    }
+
+    #[test]
+    fn compact_move_bytes() {
+        let mut m = Move::from(3, 9, 3, 2).unwrap();
+        m.add_capture(Direction::Left).unwrap();
+        m.set_win();
+
+        let bytes: [u8; 4] = m.compact().into();
+        assert_eq!(bytes, m.compact().as_u32().to_le_bytes());
+        let decoded: Move = CompactMove::from(bytes).into();
+        assert_eq!(decoded, m);
+        assert_eq!(CompactMove::from(&bytes), m.compact());
+    }
+
+    #[test]
+    fn algebraic_moves() {
+        let m = Move::from(0, 7, 3, 7).unwrap();
+        assert_eq!(m.to_algebraic(), "a4-d4");
+        assert_eq!(Move::from_algebraic("a4-d4"), Ok(m));
+
+        for s in ["k11-k2", "f6-f10", "b1-a1", "c3-c4"] {
+            assert_eq!(Move::from_algebraic(s).unwrap().to_algebraic(), s);
+        }
+
+        // captures and wins come from performing the move
+        let mut board = Board::empty();
+        board.place_piece(Piece::Attacker, 3, 3);
+        board.place_piece(Piece::Attacker, 5, 7);
+        board.place_piece(Piece::Defender, 4, 3);
+        board.place_piece(Piece::King, 1, 10);
+        let captured: Move = board.move_piece(5, 7, 5, 3).unwrap().into();
+        assert_eq!(captured.to_algebraic(), "f4xf8");
+        let won: Move = board.move_piece(1, 10, 0, 10).unwrap().into();
+        assert_eq!(won.to_algebraic(), "b1-a1#");
+
+        let parsed = Move::from_algebraic("f4xf8").unwrap();
+        assert!(parsed.same_path(&captured));
+        assert_eq!(Move::from_algebraic("b1-a1#"), Ok(won));
+
+        for s in [
+            "l1-l2", "a0-a2", "a12-a2", "a1-b2", "a1", "a1-", "a+1-a2", "A1-A2",
+        ] {
+            assert!(Move::from_algebraic(s).is_err(), "{}", s);
+        }
+        assert_eq!(
+            Move::from_algebraic("a1-b2"),
+            Err(HnefataflError::MoveNotHorVer)
+        );
+    }
+
+    #[test]
+    fn move_origin_and_target() {
+        for (to_x, to_y) in [(5, 1), (9, 5), (5, 10), (0, 5)] {
+            let m = Move::from(5, 5, to_x, to_y).unwrap();
+
+            assert_eq!(m.origin(), (5, 5));
+            assert_eq!(m.to(), (to_x, to_y));
+            assert_eq!(m.destination(), m.to());
+        }
+    }
+
+    #[test]
+    fn compact_move_accessors() {
+        let mut m = Move::from(3, 9, 3, 2).unwrap();
+        m.add_capture(Direction::Left).unwrap();
+        m.add_capture(Direction::Up).unwrap();
+        m.set_win();
+
+        for m in [m, Move::from(7, 4, 9, 4).unwrap()] {
+            let compact = m.compact();
+
+            assert_eq!(compact.is_win(), m.is_win);
+            assert_eq!(compact.capture_count(), m.capture_count as u32);
+            assert_eq!(Into::<Move>::into(compact), m);
+            assert_eq!(compact.direction(), m.direction);
+        }
+    }
+
+    #[test]
+    fn normal_capture() {
+        // Setup board
+        let mut board = Board::empty();
+        board.place_piece(Piece::Attacker, 3, 3);
+        board.place_piece(Piece::Attacker, 5, 7);
+        board.place_piece(Piece::Defender, 4, 3);
+
+        // Expected board
+        let mut expected_board = Board::empty();
+        expected_board.place_piece(Piece::Attacker, 3, 3);
+        expected_board.place_piece(Piece::Attacker, 5, 3);
+        expected_board.set_turn(Turn::White);
+
+        let expected_move = Move::from(5, 7, 5, 3).map(|mut m| {
+            m.add_capture(Direction::Left).unwrap();
+            m.compact()
+        });
+
+        // Make move
+        let captured = board.move_piece(5, 7, 5, 3);
+
+        // Test
+        assert_eq!(board, expected_board);
+        assert_eq!(captured, expected_move);
+    }
+
+    #[test]
+    fn king_wins_only_in_corner() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 0, 4);
+        board.set_turn(Turn::White);
+
+        let m = board.move_piece(0, 4, 0, 0).unwrap();
+        assert!(m.is_win());
+        assert_eq!(board.result(), GameResult::DefenderWin);
+
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 5, 8);
+        board.set_turn(Turn::White);
+
+        let m = board.move_piece(5, 8, 5, 5).unwrap();
+        assert!(!m.is_win());
+        assert_eq!(board.result(), GameResult::Ongoing);
+        assert!(board.is_king_on_throne());
+    }
+
+    /// Attackers in a diamond around (5,5), with the tile (5,1) missing
+    fn almost_encircled_board() -> Board {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 5, 5);
+        board.place_piece(Piece::Defender, 5, 4);
+
+        for i in 0..4 {
+            board.place_piece(Piece::Attacker, 1 + i, 5 - i);
+            board.place_piece(Piece::Attacker, 9 - i, 5 - i);
+            board.place_piece(Piece::Attacker, 1 + i, 5 + i);
+            board.place_piece(Piece::Attacker, 9 - i, 5 + i);
+        }
+        board.place_piece(Piece::Attacker, 5, 9);
+        board.place_piece(Piece::Attacker, 8, 1);
+        board.set_turn(Turn::Black);
+
+        board
+    }
+
+    #[test]
+    fn encirclement_wins() {
+        let mut board = almost_encircled_board();
+        assert!(!board.king_is_encircled());
+
+        let m = board.move_piece(8, 1, 5, 1).unwrap();
+        assert!(m.is_win());
+        assert!(board.king_is_encircled());
+        assert_eq!(board.get_turn(), Turn::Black);
+        assert_eq!(board.result(), GameResult::AttackerWin);
+        assert_eq!(
+            board.describe_outcome().as_deref(),
+            Some("Black wins: king encircled on move 1")
+        );
+    }
+
+    #[test]
+    fn encirclement_with_gap() {
+        let mut board = almost_encircled_board();
+
+        let m = board.move_piece(8, 1, 7, 1).unwrap();
+        assert!(!m.is_win());
+        assert!(!board.king_is_encircled());
+        assert_eq!(board.result(), GameResult::Ongoing);
+    }
+
+    /// A diamond of attackers around (3, 3), with the attacker closing it on (3, 8)
+    fn diamond_board(inside: Piece, outside: Piece) -> Board {
+        let mut board = Board::empty();
+        board.place_piece(inside, 3, 3);
+        board.place_piece(outside, 9, 1);
+        for (x, y) in [
+            (3, 1),
+            (2, 2),
+            (4, 2),
+            (1, 3),
+            (5, 3),
+            (2, 4),
+            (4, 4),
+            (3, 8),
+        ] {
+            board.place_piece(Piece::Attacker, x, y);
+        }
+        board.set_turn(Turn::Black);
+
+        board
+    }
+
+    #[test]
+    fn encirclement_needs_king() {
+        // only a defender is enclosed
+        let mut board = diamond_board(Piece::Defender, Piece::King);
+        let m = board.move_piece(3, 8, 3, 5).unwrap();
+        assert!(!m.is_win());
+        assert!(!board.king_is_encircled());
+        assert_eq!(board.result(), GameResult::Ongoing);
+
+        let mut board = diamond_board(Piece::King, Piece::Defender);
+        let m = board.move_piece(3, 8, 3, 5).unwrap();
+        assert!(m.is_win());
+        assert_eq!(board.result(), GameResult::AttackerWin);
+    }
+
+    #[test]
+    fn encirclement_of_all_defenders() {
+        let rules = RuleSet {
+            encircle_defenders: true,
+            ..Default::default()
+        };
+
+        // a defender outside the ring can still break it
+        let mut board = diamond_board(Piece::King, Piece::Defender);
+        board.set_rules(rules);
+        let m = board.move_piece(3, 8, 3, 5).unwrap();
+        assert!(!m.is_win());
+        assert!(!board.king_is_encircled());
+
+        // the king and the only defender are both enclosed
+        let mut board = diamond_board(Piece::King, Piece::Defender);
+        board.remove_piece(9, 1);
+        board.place_piece(Piece::Defender, 3, 2);
+        board.set_rules(rules);
+        let m = board.move_piece(3, 8, 3, 5).unwrap();
+        assert!(m.is_win());
+        assert_eq!(board.result(), GameResult::AttackerWin);
+    }
+
+    #[test]
+    fn exit_fort_wins() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 3, 0);
+        board.place_piece(Piece::Defender, 2, 0);
+        board.place_piece(Piece::Defender, 3, 1);
+        board.place_piece(Piece::Defender, 4, 1);
+        board.place_piece(Piece::Defender, 5, 3);
+        board.place_piece(Piece::Attacker, 8, 8);
+        board.set_turn(Turn::White);
+        assert!(!board.is_exit_fort());
+
+        let m = board.move_piece(5, 3, 5, 0).unwrap();
+        assert!(m.is_win());
+        assert!(board.is_exit_fort());
+        assert_eq!(board.result(), GameResult::DefenderWin);
+    }
+
+    #[test]
+    fn exit_fort_with_flankable_wall() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 3, 0);
+        board.place_piece(Piece::Defender, 2, 0);
+        board.place_piece(Piece::Defender, 5, 0);
+        board.place_piece(Piece::Defender, 3, 1);
+        board.place_piece(Piece::Defender, 5, 1);
+        board.place_piece(Piece::Defender, 4, 2);
+        board.place_piece(Piece::Attacker, 8, 8);
+
+        // (4,2) can be captured from (3,2) and (5,2)
+        assert!(!board.is_exit_fort());
+
+        board.place_piece(Piece::Defender, 3, 2);
+        assert!(board.is_exit_fort());
+    }
+
+    #[test]
+    fn shield_wall_capture() {
+        let mut board = Board::empty();
+        for x in 3..=5 {
+            board.place_piece(Piece::Defender, x, 10);
+            board.place_piece(Piece::Attacker, x, 9);
+        }
+        board.place_piece(Piece::Attacker, 2, 10);
+        board.place_piece(Piece::Attacker, 6, 5);
+        board.place_piece(Piece::King, 8, 2);
+        board.set_turn(Turn::Black);
+
+        let m = board.move_piece(6, 5, 6, 10).unwrap();
+
+        assert_eq!(m.capture_count(), 3);
+        assert_eq!(board.history.last().unwrap().captured.len(), 3);
+        for x in 3..=5 {
+            assert_eq!(board.get_piece_unchecked(x, 10), None);
+        }
+        assert_eq!(board.stats().white_captured, 3);
+    }
+
+    #[test]
+    fn shield_wall_needs_front_and_ends() {
+        let mut board = Board::empty();
+        for x in 3..=5 {
+            board.place_piece(Piece::Defender, x, 10);
+            board.place_piece(Piece::Attacker, x, 9);
+        }
+        board.place_piece(Piece::Attacker, 6, 5);
+        board.place_piece(Piece::King, 8, 2);
+        board.set_turn(Turn::Black);
+
+        // open at one end
+        let mut open_end = board.clone();
+        open_end.move_piece(6, 5, 6, 10).unwrap();
+        assert_eq!(open_end.defenders().count(), 3);
+
+        // open in front
+        board.place_piece(Piece::Attacker, 2, 10);
+        board.remove_piece(4, 9);
+        board.move_piece(6, 5, 6, 10).unwrap();
+        assert_eq!(board.defenders().count(), 3);
+    }
+
+    #[test]
+    fn shield_wall_closed_only_at_the_ends() {
+        let mut board = Board::empty();
+        for x in 3..=5 {
+            board.place_piece(Piece::Defender, x, 10);
+        }
+        board.place_piece(Piece::Attacker, 2, 10);
+        board.place_piece(Piece::Attacker, 6, 10);
+        board.place_piece(Piece::Attacker, 3, 9);
+        board.place_piece(Piece::Attacker, 5, 9);
+        board.place_piece(Piece::Attacker, 4, 5);
+        board.place_piece(Piece::King, 8, 2);
+        board.set_turn(Turn::Black);
+
+        // stepping in front of the wall does not close it
+        let m = board.move_piece(4, 5, 4, 9).unwrap();
+        assert_eq!(m.capture_count(), 0);
+        assert_eq!(board.defenders().count(), 3);
+    }
+
+    #[test]
+    fn king_survives_shield_wall() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::Defender, 3, 10);
+        board.place_piece(Piece::King, 4, 10);
+        board.place_piece(Piece::Defender, 5, 10);
+        for x in 3..=5 {
+            board.place_piece(Piece::Attacker, x, 9);
+        }
+        board.place_piece(Piece::Attacker, 2, 10);
+        board.place_piece(Piece::Attacker, 6, 5);
+        board.set_turn(Turn::Black);
+
+        let mut captures_king = board.clone();
+        captures_king.set_rules(RuleSet {
+            shield_wall_captures_king: true,
+            ..Default::default()
+        });
+
+        board.move_piece(6, 5, 6, 10).unwrap();
+        assert_eq!(board.defenders().count(), 0);
+        assert_eq!(board.kings().next(), Some((4, 10)));
+        assert!(!board.is_game_over());
+
+        let m = captures_king.move_piece(6, 5, 6, 10).unwrap();
+        assert!(m.is_win());
+        assert_eq!(captures_king.kings().next(), None);
+    }
+
+    #[test]
+    fn winner_for_each_ending() {
+        let board = Board::new();
+        assert!(!board.is_won());
+        assert_eq!(board.winner(), None);
+
+        // king captured
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 4, 5);
+        board.place_piece(Piece::Attacker, 4, 4);
+        board.place_piece(Piece::Attacker, 4, 6);
+        board.place_piece(Piece::Attacker, 1, 5);
+        board.set_turn(Turn::Black);
+        board.move_piece(1, 5, 3, 5).unwrap();
+        assert!(board.is_won());
+        assert_eq!(board.winner(), Some(Turn::Black));
+
+        // king encircled
+        let mut board = almost_encircled_board();
+        board.move_piece(8, 1, 5, 1).unwrap();
+        assert_eq!(board.winner(), Some(Turn::Black));
 
-        assert_eq!(board.get_piece_unchecked(0, 3), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(1, 3), None);
-        assert_eq!(board.get_piece_unchecked(2, 3), None);
-        assert_eq!(board.get_piece_unchecked(3, 3), None);
-        assert_eq!(board.get_piece_unchecked(4, 3), None);
-        assert_eq!(board.get_piece_unchecked(5, 3), Some(Defender));
-        assert_eq!(board.get_piece_unchecked(6, 3), None);
-        assert_eq!(board.get_piece_unchecked(7, 3), None);
-        assert_eq!(board.get_piece_unchecked(8, 3), None);
-        assert_eq!(board.get_piece_unchecked(9, 3), None);
-        assert_eq!(board.get_piece_unchecked(10, 3), Some(Attacker));
+        // king escaped, by the side that moved rather than the side to move
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 0, 4);
+        board.set_turn(Turn::White);
+        board.move_piece(0, 4, 0, 0).unwrap();
+        assert!(board.is_won());
+        assert_eq!(board.winner(), Some(Turn::White));
+
+        board.set_result(GameResult::Draw);
+        assert!(board.is_game_over());
+        assert!(!board.is_won());
+        assert_eq!(board.winner(), None);
+    }
 
-        assert_eq!(board.get_piece_unchecked(0, 4), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(1, 4), None);
-        assert_eq!(board.get_piece_unchecked(2, 4), None);
-        assert_eq!(board.get_piece_unchecked(3, 4), None);
-        assert_eq!(board.get_piece_unchecked(4, 4), Some(Defender));
-        assert_eq!(board.get_piece_unchecked(5, 4), Some(Defender));
-        assert_eq!(board.get_piece_unchecked(6, 4), Some(Defender));
-        assert_eq!(board.get_piece_unchecked(7, 4), None);
-        assert_eq!(board.get_piece_unchecked(8, 4), None);
-        assert_eq!(board.get_piece_unchecked(9, 4), None);
-        assert_eq!(board.get_piece_unchecked(10, 4), Some(Attacker));
+    #[test]
+    fn no_moves_loses() {
+        // the last attacker is boxed in by the king and the defenders
+        let mut board = Board::empty();
+        board.place_piece(Piece::Attacker, 0, 5);
+        board.place_piece(Piece::Defender, 0, 4);
+        board.place_piece(Piece::Defender, 0, 6);
+        board.place_piece(Piece::King, 4, 5);
+        board.set_turn(Turn::White);
+        assert!(board.has_legal_moves());
 
-        assert_eq!(board.get_piece_unchecked(0, 5), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(1, 5), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(2, 5), None);
-        assert_eq!(board.get_piece_unchecked(3, 5), Some(Defender));
-        assert_eq!(board.get_piece_unchecked(4, 5), Some(Defender));
-        assert_eq!(board.get_piece_unchecked(5, 5), Some(King));
-        assert_eq!(board.get_piece_unchecked(6, 5), Some(Defender));
-        assert_eq!(board.get_piece_unchecked(7, 5), Some(Defender));
-        assert_eq!(board.get_piece_unchecked(8, 5), None);
-        assert_eq!(board.get_piece_unchecked(9, 5), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(10, 5), Some(Attacker));
+        let m = board.move_piece(4, 5, 1, 5).unwrap();
+        assert!(m.is_win());
+        assert_eq!(board.result(), GameResult::DefenderWin);
+        assert_eq!(board.winner(), Some(Turn::White));
+        assert_eq!(board.get_turn(), Turn::White);
+        assert_eq!(
+            board.describe_outcome().as_deref(),
+            Some("White wins: black has no moves on move 1")
+        );
 
-        assert_eq!(board.get_piece_unchecked(0, 6), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(1, 6), None);
-        assert_eq!(board.get_piece_unchecked(2, 6), None);
-        assert_eq!(board.get_piece_unchecked(3, 6), None);
-        assert_eq!(board.get_piece_unchecked(4, 6), Some(Defender));
-        assert_eq!(board.get_piece_unchecked(5, 6), Some(Defender));
-        assert_eq!(board.get_piece_unchecked(6, 6), Some(Defender));
-        assert_eq!(board.get_piece_unchecked(7, 6), None);
-        assert_eq!(board.get_piece_unchecked(8, 6), None);
-        assert_eq!(board.get_piece_unchecked(9, 6), None);
-        assert_eq!(board.get_piece_unchecked(10, 6), Some(Attacker));
+        board.set_turn(Turn::Black);
+        assert!(!board.has_legal_moves());
+        board.undo().unwrap();
+        assert_eq!(board.result(), GameResult::Ongoing);
 
-        assert_eq!(board.get_piece_unchecked(0, 7), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(1, 7), None);
-        assert_eq!(board.get_piece_unchecked(2, 7), None);
-        assert_eq!(board.get_piece_unchecked(3, 7), None);
-        assert_eq!(board.get_piece_unchecked(4, 7), None);
-        assert_eq!(board.get_piece_unchecked(5, 7), Some(Defender));
-        assert_eq!(board.get_piece_unchecked(6, 7), None);
-        assert_eq!(board.get_piece_unchecked(7, 7), None);
-        assert_eq!(board.get_piece_unchecked(8, 7), None);
-        assert_eq!(board.get_piece_unchecked(9, 7), None);
-        assert_eq!(board.get_piece_unchecked(10, 7), Some(Attacker));
+        // the lone king is boxed in on the edge, without being captured
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 3, 0);
+        board.place_piece(Piece::Attacker, 2, 0);
+        board.place_piece(Piece::Attacker, 4, 0);
+        board.place_piece(Piece::Attacker, 3, 5);
 
-        assert_eq!(board.get_piece_unchecked(0, 8), None);
-        assert_eq!(board.get_piece_unchecked(1, 8), None);
-        assert_eq!(board.get_piece_unchecked(2, 8), None);
-        assert_eq!(board.get_piece_unchecked(3, 8), None);
-        assert_eq!(board.get_piece_unchecked(4, 8), None);
-        assert_eq!(board.get_piece_unchecked(5, 8), None);
-        assert_eq!(board.get_piece_unchecked(6, 8), None);
-        assert_eq!(board.get_piece_unchecked(7, 8), None);
-        assert_eq!(board.get_piece_unchecked(8, 8), None);
-        assert_eq!(board.get_piece_unchecked(9, 8), None);
-        assert_eq!(board.get_piece_unchecked(10, 8), None);
+        board.move_piece(3, 5, 3, 1).unwrap();
+        assert_eq!(board.king_position(), Some((3, 0)));
+        assert_eq!(board.winner(), Some(Turn::Black));
+        assert_eq!(
+            board.describe_outcome().as_deref(),
+            Some("Black wins: white has no moves on move 1")
+        );
+    }
 
-        assert_eq!(board.get_piece_unchecked(0, 9), None);
-        assert_eq!(board.get_piece_unchecked(1, 9), None);
-        assert_eq!(board.get_piece_unchecked(2, 9), None);
-        assert_eq!(board.get_piece_unchecked(3, 9), None);
+    #[test]
+    fn result_for_each_ending() {
+        assert_eq!(Board::new().result(), GameResult::Ongoing);
+
+        // king captured
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 4, 5);
+        board.place_piece(Piece::Attacker, 4, 4);
+        board.place_piece(Piece::Attacker, 4, 6);
+        board.place_piece(Piece::Attacker, 1, 5);
+        board.set_turn(Turn::Black);
+        board.move_piece(1, 5, 3, 5).unwrap();
+        assert_eq!(board.result(), GameResult::AttackerWin);
+
+        // king encircled
+        let mut board = almost_encircled_board();
+        board.move_piece(8, 1, 5, 1).unwrap();
+        assert_eq!(board.result(), GameResult::AttackerWin);
+
+        // king escaped
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 0, 4);
+        board.set_turn(Turn::White);
+        board.move_piece(0, 4, 0, 0).unwrap();
+        assert_eq!(board.result(), GameResult::DefenderWin);
+
+        // the result is kept through checkpoints
+        let checkpoint = board.checkpoint();
+        let mut restored = Board::empty();
+        restored.restore(&checkpoint);
+        assert_eq!(restored.result(), GameResult::DefenderWin);
+    }
+
+    #[test]
+    fn threefold_repetition() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 5, 2);
+        board.place_piece(Piece::Attacker, 8, 8);
+        board.set_turn(Turn::White);
+
+        let shuffle = [(5, 2, 4, 2), (8, 8, 8, 7), (4, 2, 5, 2), (8, 7, 8, 8)];
+        for (i, &(x, y, to_x, to_y)) in shuffle.iter().cycle().take(8).enumerate() {
+            assert!(!board.is_draw_by_repetition(), "draw after {} moves", i);
+            board.move_piece(x, y, to_x, to_y).unwrap();
+        }
+
+        assert!(board.is_draw_by_repetition());
+        assert_eq!(board.result(), GameResult::Draw);
+        assert_eq!(
+            board.move_piece(5, 2, 4, 2),
+            Err(HnefataflError::GameAlreadyWon)
+        );
+    }
+
+    #[test]
+    fn capture_against_last_column() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::Attacker, 10, 4);
+        board.place_piece(Piece::Attacker, 8, 7);
+        board.place_piece(Piece::Defender, 9, 4);
+
+        let captured = board.move_piece(8, 7, 8, 4).unwrap();
+
+        assert_eq!(captured.capture_count(), 1);
+        assert_eq!(board.get_piece_unchecked(9, 4), None);
+    }
+
+    #[test]
+    fn capture_against_last_row() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::Attacker, 4, 10);
+        board.place_piece(Piece::Attacker, 7, 8);
+        board.place_piece(Piece::Defender, 4, 9);
+
+        let captured = board.move_piece(7, 8, 4, 8).unwrap();
+
+        assert_eq!(captured.capture_count(), 1);
         assert_eq!(board.get_piece_unchecked(4, 9), None);
-        assert_eq!(board.get_piece_unchecked(5, 9), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(6, 9), None);
-        assert_eq!(board.get_piece_unchecked(7, 9), None);
-        assert_eq!(board.get_piece_unchecked(8, 9), None);
-        assert_eq!(board.get_piece_unchecked(9, 9), None);
-        assert_eq!(board.get_piece_unchecked(10, 9), None);
+    }
 
-        assert_eq!(board.get_piece_unchecked(0, 10), None);
-        assert_eq!(board.get_piece_unchecked(1, 10), None);
-        assert_eq!(board.get_piece_unchecked(2, 10), None);
-        assert_eq!(board.get_piece_unchecked(3, 10), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(4, 10), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(5, 10), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(6, 10), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(7, 10), Some(Attacker));
-        assert_eq!(board.get_piece_unchecked(8, 10), None);
+    #[test]
+    fn capturing_with_far_corner_assistance() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::Attacker, 8, 5);
+        board.place_piece(Piece::Defender, 9, 10);
+
+        let captured = board.move_piece(8, 5, 8, 10).unwrap();
+
+        assert_eq!(captured.capture_count(), 1);
         assert_eq!(board.get_piece_unchecked(9, 10), None);
-        assert_eq!(board.get_piece_unchecked(10, 10), None);
-        // }}}
     }
 
     #[test]
-    fn test_move_unchecked() {
-        let mut board = Board::new();
+    fn capturing_with_fortress_assistance() {
+        // Setup board
+        let mut board = Board::empty();
+        board.place_piece(Piece::Attacker, 1, 0);
+        board.place_piece(Piece::Defender, 2, 3);
+        board.set_turn(Turn::White);
+
+        // Expected board
+        let mut expected_board = Board::empty();
+        expected_board.place_piece(Piece::Defender, 2, 0);
+        expected_board.set_turn(Turn::Black);
+
+        let expected_move = Move::from(2, 3, 2, 0).map(|mut m| {
+            m.add_capture(Direction::Left).unwrap();
+            m.compact()
+        });
+
+        // Make move
+        let captured = board.move_piece(2, 3, 2, 0);
+
+        assert_eq!(board, expected_board);
+        assert_eq!(captured, expected_move);
+    }
+
+    #[test]
+    fn no_capture_against_corner_when_corners_not_hostile() {
+        let mut board = Board::empty();
+        board.set_rules(RuleSet {
+            corners_hostile: false,
+            ..Default::default()
+        });
+        board.place_piece(Piece::Attacker, 1, 0);
+        board.place_piece(Piece::Defender, 2, 3);
+        board.set_turn(Turn::White);
+
+        board.move_piece(2, 3, 2, 0).unwrap();
+
+        assert_eq!(board.get_piece_unchecked(1, 0), Some(Piece::Attacker));
+    }
+
+    #[test]
+    fn center_is_ordinary_without_throne() {
+        let mut board = Board::empty();
+        board.set_rules(RuleSet {
+            has_throne: false,
+            ..Default::default()
+        });
+        board.place_piece(Piece::Attacker, 5, 1);
+        board.place_piece(Piece::Defender, 4, 5);
+        board.place_piece(Piece::Defender, 8, 5);
+
+        assert!(board.moves_from(5, 1).contains(&(5, 5)));
+        assert!(board.move_piece(5, 1, 5, 5).is_ok());
+
+        // the empty center is no anvil
+        board.move_piece_uncheced(5, 5, 3, 3);
+        board.set_turn(Turn::Black);
+        board.move_piece(3, 3, 3, 5).unwrap();
+        assert_eq!(board.get_piece_unchecked(4, 5), Some(Piece::Defender));
+
+        // the king does not win on the center
+        let mut board = Board::empty();
+        board.set_rules(RuleSet {
+            has_throne: false,
+            ..Default::default()
+        });
+        board.set_turn(Turn::White);
+        board.place_piece(Piece::King, 5, 8);
+        board.move_piece(5, 8, 5, 5).unwrap();
+        assert!(!board.is_game_over());
+        assert!(!board.is_king_on_throne());
+    }
+
+    #[test]
+    fn attacker_captured_against_occupied_throne() {
+        let mut board = Board::empty();
+        board.set_turn(Turn::White);
+        board.place_piece(Piece::King, 5, 5);
+        board.place_piece(Piece::Attacker, 5, 4);
+        board.place_piece(Piece::Defender, 2, 3);
+
+        board.move_piece(2, 3, 5, 3).unwrap();
+
+        assert_eq!(board.get_piece_unchecked(5, 4), None);
+    }
+
+    #[test]
+    fn throne_immunity() {
+        let mut board = Board::empty();
+        board.set_turn(Turn::White);
+        board.place_piece(Piece::King, 5, 5);
+        board.place_piece(Piece::Attacker, 5, 4);
+        board.place_piece(Piece::Defender, 2, 3);
+
+        let mut immune = board.clone();
+        immune.set_rules(RuleSet {
+            throne_immunity: true,
+            ..Default::default()
+        });
+
+        board.move_piece(2, 3, 5, 3).unwrap();
+        assert_eq!(board.get_piece_unchecked(5, 4), None);
+
+        immune.move_piece(2, 3, 5, 3).unwrap();
+        assert_eq!(immune.get_piece_unchecked(5, 4), Some(Piece::Attacker));
+    }
+
+    #[test]
+    fn unarmed_king() {
+        let mut board = Board::empty();
+        board.set_turn(Turn::White);
+        board.place_piece(Piece::King, 3, 3);
+        board.place_piece(Piece::Attacker, 4, 3);
+        board.place_piece(Piece::Defender, 5, 7);
+        board.place_piece(Piece::Attacker, 8, 6);
+
+        let mut unarmed = board.clone();
+        unarmed.set_rules(RuleSet {
+            armed_king: false,
+            ..Default::default()
+        });
+
+        // the king as the anvil
+        board.move_piece(5, 7, 5, 3).unwrap();
+        assert_eq!(board.get_piece_unchecked(4, 3), None);
+        unarmed.move_piece(5, 7, 5, 3).unwrap();
+        assert_eq!(unarmed.get_piece_unchecked(4, 3), Some(Piece::Attacker));
+
+        // the king as the hammer
+        board.set_turn(Turn::White);
+        unarmed.set_turn(Turn::White);
+        board.place_piece(Piece::Defender, 8, 5);
+        unarmed.place_piece(Piece::Defender, 8, 5);
+        board.move_piece(3, 3, 3, 7).unwrap();
+        board.set_turn(Turn::White);
+        board.move_piece(3, 7, 8, 7).unwrap();
+        assert_eq!(board.get_piece_unchecked(8, 6), None);
+        unarmed.move_piece(3, 3, 3, 7).unwrap();
+        unarmed.set_turn(Turn::White);
+        unarmed.move_piece(3, 7, 8, 7).unwrap();
+        assert_eq!(unarmed.get_piece_unchecked(8, 6), Some(Piece::Attacker));
+    }
+
+    #[test]
+    fn king_captured_against_hostile_throne() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 5, 4);
+        board.place_piece(Piece::Attacker, 4, 4);
+        board.place_piece(Piece::Attacker, 6, 4);
+        board.place_piece(Piece::Attacker, 5, 0);
+
+        let mut friendly = board.clone();
+        friendly.set_rules(RuleSet {
+            hostile_throne: false,
+            ..Default::default()
+        });
+
+        board.move_piece(5, 0, 5, 3).unwrap();
+        assert_eq!(board.king_position(), None);
+        assert_eq!(board.result(), GameResult::AttackerWin);
+
+        friendly.move_piece(5, 0, 5, 3).unwrap();
+        assert_eq!(friendly.king_position(), Some((5, 4)));
+        assert_eq!(friendly.result(), GameResult::Ongoing);
+    }
+
+    #[test]
+    fn king_captured_against_edge() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 0, 5);
+        board.place_piece(Piece::Attacker, 0, 4);
+        board.place_piece(Piece::Attacker, 0, 6);
+        board.place_piece(Piece::Attacker, 4, 5);
+
+        let mut edge = board.clone();
+        edge.set_rules(RuleSet {
+            edge_king_capture: true,
+            ..Default::default()
+        });
+
+        board.move_piece(4, 5, 1, 5).unwrap();
+        assert_eq!(board.king_position(), Some((0, 5)));
+
+        edge.move_piece(4, 5, 1, 5).unwrap();
+        assert_eq!(edge.king_position(), None);
+        assert_eq!(edge.result(), GameResult::AttackerWin);
+    }
+
+    #[test]
+    fn king_captured_next_to_corner() {
+        let mut board = Board::empty();
+        board.set_rules(RuleSet {
+            edge_king_capture: true,
+            ..Default::default()
+        });
+        board.place_piece(Piece::King, 0, 1);
+        board.place_piece(Piece::Attacker, 0, 2);
+        board.place_piece(Piece::Attacker, 1, 5);
+
+        board.move_piece(1, 5, 1, 1).unwrap();
+        assert_eq!(board.king_position(), None);
+        assert_eq!(board.result(), GameResult::AttackerWin);
+    }
+
+    #[test]
+    fn attacker_captured_against_empty_throne() {
+        let mut board = Board::empty();
+        board.set_turn(Turn::White);
+        board.place_piece(Piece::Attacker, 5, 4);
+        board.place_piece(Piece::Defender, 2, 3);
 
-        board.move_piece_uncheced(0, 7, 5, 7);
+        board.move_piece(2, 3, 5, 3).unwrap();
 
-        assert_eq!(board.get_piece_unchecked(0, 7), None);
-        assert_eq!(board.get_piece_unchecked(5, 7), Some(Piece::Attacker));
+        assert_eq!(board.get_piece_unchecked(5, 4), None);
     }
 
     #[test]
-    fn test_move() {
-        let mut board = Board::new();
+    fn defender_captured_against_empty_throne() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::Defender, 4, 5);
+        board.place_piece(Piece::Attacker, 3, 8);
 
-        // {{{ Errors on move
-        assert_eq!(
-            board.move_piece(1, 7, 4, 7),
-            Err(HnefataflError::NoPieceToMove)
-        );
+        board.move_piece(3, 8, 3, 5).unwrap();
 
-        assert_eq!(
-            board.move_piece(0, 3, 0, 0),
-            Err(HnefataflError::IsProtectedTile)
-        );
+        assert_eq!(board.get_piece_unchecked(4, 5), None);
+    }
 
-        assert_eq!(
-            board.move_piece(0, 7, 3, 9),
-            Err(HnefataflError::MoveNotHorVer)
-        );
+    #[test]
+    fn defender_not_captured_against_occupied_throne() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 5, 5);
+        board.place_piece(Piece::Defender, 4, 5);
+        board.place_piece(Piece::Attacker, 3, 8);
 
-        assert_eq!(
-            board.move_piece(0, 7, -2, 7),
-            Err(HnefataflError::TargetOutOfBounds)
-        );
+        board.move_piece(3, 8, 3, 5).unwrap();
 
-        assert_eq!(
-            board.move_piece(0, 7, 0, 11),
-            Err(HnefataflError::TargetOutOfBounds)
-        );
+        assert_eq!(board.get_piece_unchecked(4, 5), Some(Piece::Defender));
+    }
 
-        assert_eq!(
-            board.move_piece(-4, 7, 3, 7),
-            Err(HnefataflError::StartOutOfBounds)
-        );
+    #[test]
+    fn count_enemies_around_king() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 3, 3);
+        board.place_piece(Piece::Defender, 3, 4);
+        assert_eq!(board.surrounding_enemy_count(3, 3), 0);
 
-        assert_eq!(
-            board.move_piece(0, 19, 0, 7),
-            Err(HnefataflError::StartOutOfBounds)
-        );
+        board.place_piece(Piece::Attacker, 2, 3);
+        assert_eq!(board.surrounding_enemy_count(3, 3), 1);
 
-        assert_eq!(
-            board.move_piece(0, 7, 5, 7),
-            Err(HnefataflError::PieceInTheWay)
-        );
+        board.place_piece(Piece::Attacker, 4, 3);
+        board.place_piece(Piece::Attacker, 3, 2);
+        assert_eq!(board.surrounding_enemy_count(3, 3), 3);
 
-        assert_eq!(
-            board.move_piece(3, 5, 2, 5),
-            Err(HnefataflError::WrongPieceColor)
-        );
-        // }}}
+        // the empty throne is hostile
+        board.place_piece(Piece::King, 5, 4);
+        assert_eq!(board.surrounding_enemy_count(5, 4), 1);
 
-        assert_eq!(
-            board.move_piece(0, 7, 4, 7),
-            Move::from(0, 7, 4, 7).map(|m| m.compact())
-        );
-        assert_eq!(board.get_piece_unchecked(0, 7), None);
-        assert_eq!(board.get_piece_unchecked(4, 7), Some(Piece::Attacker));
+        assert_eq!(board.surrounding_enemy_count(8, 8), 0);
     }
 
     #[test]
-    fn normal_capture() {
-        // Setup board
-        let mut board = Board::empty();
-        board.place_piece(Piece::Attacker, 3, 3);
-        board.place_piece(Piece::Attacker, 5, 7);
-        board.place_piece(Piece::Defender, 4, 3);
+    fn walled_in_king_can_not_escape() {
+        assert!(Board::new().could_king_escape());
 
-        // Expected board
-        let mut expected_board = Board::empty();
-        expected_board.place_piece(Piece::Attacker, 3, 3);
-        expected_board.place_piece(Piece::Attacker, 5, 3);
-        expected_board.set_turn(Turn::White);
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 5, 5);
+        board.place_piece(Piece::Defender, 5, 4);
 
-        let expected_move = Move::from(5, 7, 5, 3).map(|mut m| {
-            m.add_capture(Direction::Left).unwrap();
-            m.compact()
-        });
+        // a wall around the king, with a gap on top
+        for i in 3..=7 {
+            board.place_piece(Piece::Attacker, i, 2);
+            board.place_piece(Piece::Attacker, i, 8);
+            board.place_piece(Piece::Attacker, 2, i);
+            board.place_piece(Piece::Attacker, 8, i);
+        }
+        board.remove_piece(5, 2);
+        assert!(board.could_king_escape());
 
-        // Make move
-        let captured = board.move_piece(5, 7, 5, 3);
+        board.place_piece(Piece::Attacker, 5, 2);
+        assert!(!board.could_king_escape());
 
-        // Test
-        assert_eq!(board, expected_board);
-        assert_eq!(captured, expected_move);
+        assert!(!Board::empty().could_king_escape());
     }
 
     #[test]
-    fn capturing_with_fortress_assistance() {
-        // Setup board
+    fn wall_gap_around_king() {
         let mut board = Board::empty();
-        board.place_piece(Piece::Attacker, 1, 0);
-        board.place_piece(Piece::Defender, 2, 3);
-        board.set_turn(Turn::White);
+        board.place_piece(Piece::King, 5, 5);
+        board.place_piece(Piece::Defender, 4, 5);
 
-        // Expected board
-        let mut expected_board = Board::empty();
-        expected_board.place_piece(Piece::Defender, 2, 0);
-        expected_board.set_turn(Turn::Black);
+        // a ring of attackers two tiles from the king
+        for i in 3..=7 {
+            board.place_piece(Piece::Attacker, i, 3);
+            board.place_piece(Piece::Attacker, i, 7);
+            board.place_piece(Piece::Attacker, 3, i);
+            board.place_piece(Piece::Attacker, 7, i);
+        }
+        assert_eq!(board.shortest_wall_gap(), Some(0));
 
-        let expected_move = Move::from(2, 3, 2, 0).map(|mut m| {
-            m.add_capture(Direction::Left).unwrap();
-            m.compact()
-        });
+        board.remove_piece(3, 5);
+        assert_eq!(board.shortest_wall_gap(), Some(1));
 
-        // Make move
-        let captured = board.move_piece(2, 3, 2, 0);
+        board.remove_piece(5, 7);
+        assert_eq!(board.shortest_wall_gap(), Some(2));
 
-        assert_eq!(board, expected_board);
-        assert_eq!(captured, expected_move);
+        // defenders leading the king to the edge can be captured to close the gap
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 2, 5);
+        board.place_piece(Piece::Defender, 1, 5);
+        board.place_piece(Piece::Defender, 0, 5);
+        board.place_piece(Piece::Attacker, 2, 4);
+        board.place_piece(Piece::Attacker, 2, 6);
+        board.place_piece(Piece::Attacker, 3, 5);
+        assert_eq!(board.shortest_wall_gap(), Some(2));
+
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 0, 5);
+        assert_eq!(board.shortest_wall_gap(), None);
     }
 
     #[test]
@@ -940,7 +5283,7 @@ mod tests {
         expected_board.place_piece(Piece::Attacker, 3, 5);
         // since it is a win, the turn should not change
         expected_board.set_turn(Turn::Black);
-        expected_board.set_won(true);
+        expected_board.set_result(GameResult::AttackerWin);
 
         let expected_move = Move::from(1, 5, 3, 5).map(|mut m| {
             m.add_capture(Direction::Right).unwrap();
@@ -955,6 +5298,163 @@ mod tests {
         assert_eq!(performed_move, expected_move);
     }
 
+    #[test]
+    fn apply_several_moves() {
+        let moves = [
+            Move::from(0, 7, 4, 7).unwrap(),
+            Move::from(5, 3, 8, 3).unwrap(),
+            Move::from(4, 7, 4, 8).unwrap(),
+        ];
+
+        let mut board = Board::new();
+        assert_eq!(board.apply_moves(&moves), Ok(()));
+        assert_eq!(board.get_piece_unchecked(4, 8), Some(Piece::Attacker));
+        assert_eq!(board.get_turn(), Turn::White);
+
+        let moves = [
+            Move::from(0, 7, 4, 7).unwrap(),
+            Move::from(0, 3, 2, 3).unwrap(),
+            Move::from(5, 3, 8, 3).unwrap(),
+        ];
+
+        let mut board = Board::new();
+        assert_eq!(
+            board.apply_moves(&moves),
+            Err((1, HnefataflError::WrongPieceColor))
+        );
+
+        let mut expected = Board::new();
+        expected.move_piece(0, 7, 4, 7).unwrap();
+        assert_eq!(board, expected);
+    }
+
+    #[test]
+    fn step_returns_outcome() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 4, 5);
+        board.place_piece(Piece::Attacker, 4, 4);
+        board.place_piece(Piece::Attacker, 4, 6);
+        board.place_piece(Piece::Attacker, 1, 5);
+
+        let (next, outcome) = board.step(&Move::from(1, 5, 3, 5).unwrap()).unwrap();
+
+        assert_eq!(outcome, GameResult::AttackerWin);
+        assert_eq!(next.result(), GameResult::AttackerWin);
+        assert_eq!(board.result(), GameResult::Ongoing);
+
+        let (_, outcome) = board.step(&Move::from(4, 4, 3, 4).unwrap()).unwrap();
+        assert_eq!(outcome, GameResult::Ongoing);
+    }
+
+    #[test]
+    fn winning_moves() {
+        let mut board = Board::empty();
+        board.set_turn(Turn::White);
+        board.place_piece(Piece::King, 0, 6);
+        board.place_piece(Piece::Attacker, 0, 3);
+
+        // escaping to the corner
+        assert!(board.is_move_winning(&Move::from(0, 6, 0, 10).unwrap()));
+        assert!(!board.is_move_winning(&Move::from(0, 6, 0, 9).unwrap()));
+        assert!(!board.is_move_winning(&Move::from(0, 6, 0, 0).unwrap()));
+
+        // capturing the king
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 4, 5);
+        board.place_piece(Piece::Attacker, 4, 4);
+        board.place_piece(Piece::Attacker, 4, 6);
+        board.place_piece(Piece::Attacker, 1, 5);
+
+        assert!(board.is_move_winning(&Move::from(1, 5, 3, 5).unwrap()));
+        assert!(!board.is_move_winning(&Move::from(1, 5, 2, 5).unwrap()));
+        assert_eq!(board.get_piece_unchecked(1, 5), Some(Piece::Attacker));
+    }
+
+    #[test]
+    fn find_winning_move() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 0, 6);
+        board.place_piece(Piece::Attacker, 0, 3);
+        board.place_piece(Piece::Attacker, 7, 0);
+
+        assert_eq!(
+            board.has_winning_move(Turn::White),
+            Some(Move::from(0, 6, 0, 10).unwrap())
+        );
+        assert_eq!(board.has_winning_move(Turn::Black), None);
+
+        board.place_piece(Piece::Attacker, 0, 8);
+        assert_eq!(board.has_winning_move(Turn::White), None);
+    }
+
+    #[test]
+    fn king_capture_threat() {
+        let mut board = Board::empty();
+        board.set_turn(Turn::White);
+        board.place_piece(Piece::King, 2, 2);
+        board.place_piece(Piece::Attacker, 2, 1);
+        board.place_piece(Piece::Attacker, 1, 2);
+        board.place_piece(Piece::Attacker, 8, 2);
+
+        assert_eq!(board.king_capturable_next(), None);
+
+        board.place_piece(Piece::Attacker, 2, 3);
+        assert_eq!(
+            board.king_capturable_next(),
+            Some(Move::from(8, 2, 3, 2).unwrap())
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_moves_are_legal() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut board = Board::new();
+
+        for _ in 0..50 {
+            let m = match board.random_move(&mut rng) {
+                Some(m) => m,
+                None => break,
+            };
+            assert!(board.is_legal(&m));
+            board.do_move(&m).unwrap();
+        }
+        assert!(board.ply > 0);
+
+        board.set_result(GameResult::Draw);
+        assert_eq!(board.random_move(&mut rng), None);
+        assert_eq!(Board::empty().random_move(&mut rng), None);
+    }
+
+    #[test]
+    fn checked_moves() {
+        let mut board = Board::empty();
+        board.set_turn(Turn::White);
+        board.place_piece(Piece::King, 4, 8);
+        board.place_piece(Piece::Attacker, 3, 6);
+        board.place_piece(Piece::Attacker, 5, 6);
+        board.place_piece(Piece::Attacker, 4, 5);
+        // can close the fourth side of (4, 6)
+        board.place_piece(Piece::Attacker, 8, 7);
+
+        let into_capture = Move::from(4, 8, 4, 6).unwrap();
+        let safe = Move::from(4, 8, 4, 7).unwrap();
+
+        assert!(board.available_moves().contains(&into_capture));
+        assert!(!board.available_moves_checked().contains(&into_capture));
+        assert!(board.available_moves_checked().contains(&safe));
+        assert_eq!(
+            board.available_moves_checked().len(),
+            board.available_moves().len() - 1
+        );
+
+        board.set_result(GameResult::AttackerWin);
+        assert!(!board.available_moves().is_empty());
+        assert!(board.available_moves_checked().is_empty());
+    }
+
     #[test]
     fn available_moves_from_king_include_fortress() {
         let mut board = Board::empty();
@@ -1072,5 +5572,172 @@ mod tests {
             assert!(available_moves.contains(&expected_move));
         }
     }
+
+    #[test]
+    fn sorted_moves_win_first() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 4, 5);
+        board.place_piece(Piece::Attacker, 4, 4);
+        board.place_piece(Piece::Attacker, 4, 6);
+        board.place_piece(Piece::Attacker, 1, 5);
+        board.place_piece(Piece::Defender, 0, 1);
+        board.place_piece(Piece::Attacker, 2, 2);
+
+        let moves = board.legal_moves_sorted();
+
+        assert_eq!(moves.len(), board.available_moves().len());
+        assert!(moves[0].is_win);
+        assert!(moves[0].same_path(&Move::from(1, 5, 3, 5).unwrap()));
+
+        // then the capture of the defender, then quiet moves
+        assert_eq!(moves[1].capture_count, 1);
+        assert!(moves[1].same_path(&Move::from(2, 2, 0, 2).unwrap()));
+        assert!(moves[2..].iter().all(|m| m.capture_count == 0));
+    }
+
+    #[test]
+    fn perft_counts() {
+        let board = Board::new();
+        assert_eq!(board.perft(0), 1);
+        assert_eq!(board.perft(1), 116);
+        assert_eq!(board.perft(1), board.available_moves().len() as u64);
+        assert_eq!(board.perft(2), 6788);
+
+        // no moves are played after the king reaches the corner
+        let mut board = Board::empty();
+        board.set_turn(Turn::White);
+        board.place_piece(Piece::King, 0, 1);
+        board.place_piece(Piece::Attacker, 10, 5);
+        board.move_piece(0, 1, 0, 0).unwrap();
+        assert_eq!(board.perft(0), 1);
+        assert_eq!(board.perft(1), 0);
+    }
+
+    #[test]
+    fn move_count_for_both_sides() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::King, 0, 5);
+        board.place_piece(Piece::Attacker, 3, 5);
+        board.place_piece(Piece::Defender, 0, 9);
+
+        assert_eq!(board.legal_move_count_by_side(), (23, 13));
+
+        board.set_turn(Turn::White);
+        assert_eq!(board.legal_move_count_by_side(), (23, 13));
+    }
+
+    #[test]
+    fn material_and_mobility() {
+        let mut board = Board::new();
+        assert_eq!(board.material(Turn::White), 12);
+        assert_eq!(board.material(Turn::Black), 24);
+        assert!(board.mobility(Turn::White) > 0);
+        assert_eq!(board.mobility(Turn::Black), 116);
+        assert_eq!(board.get_turn(), Turn::Black);
+
+        board.remove_piece(5, 3);
+        board.remove_piece(3, 0);
+        assert_eq!(board.material(Turn::White), 11);
+        assert_eq!(board.material(Turn::Black), 23);
+    }
+
+    #[test]
+    fn capture_targets_of_each_side() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::Defender, 2, 2);
+        board.place_piece(Piece::Attacker, 1, 2);
+        board.place_piece(Piece::Attacker, 3, 6);
+        board.place_piece(Piece::King, 8, 8);
+        board.set_turn(Turn::White);
+
+        // only (3, 6) -> (3, 2) captures
+        assert_eq!(board.capture_targets(Turn::Black), vec![(2, 2)]);
+        assert_eq!(board.capture_targets(Turn::White), vec![]);
+        // the board is left as it was
+        assert_eq!(board.get_turn(), Turn::White);
+        assert_eq!(board.ply, 0);
+
+        assert_eq!(Board::new().capture_targets(Turn::Black), vec![]);
+    }
+
+    #[test]
+    fn pieces_reaching_square() {
+        let board = Board::new();
+
+        let mut attackers = board.pieces_attacking_square(5, 2, Turn::Black);
+        attackers.sort();
+        assert_eq!(attackers, vec![(5, 1)]);
+
+        let mut attackers = board.pieces_attacking_square(3, 3, Turn::Black);
+        attackers.sort();
+        assert_eq!(attackers, vec![(0, 3), (3, 0)]);
+
+        let mut defenders = board.pieces_attacking_square(3, 3, Turn::White);
+        defenders.sort();
+        assert_eq!(defenders, vec![(3, 5), (5, 3)]);
+        assert_eq!(board.pieces_attacking_square(5, 3, Turn::White), vec![]);
+
+        // only the king can reach a fortress
+        let mut board = Board::empty();
+        board.place_piece(Piece::Attacker, 0, 4);
+        board.place_piece(Piece::King, 4, 0);
+        assert_eq!(board.pieces_attacking_square(0, 0, Turn::Black), vec![]);
+        assert_eq!(
+            board.pieces_attacking_square(0, 0, Turn::White),
+            vec![(4, 0)]
+        );
+    }
+
+    #[test]
+    fn available_moves_only_king() {
+        let mut board = Board::empty();
+        board.set_turn(Turn::White);
+        board.place_piece(Piece::King, 0, 5);
+        board.place_piece(Piece::Attacker, 3, 5);
+        board.place_piece(Piece::Defender, 0, 9);
+
+        let moves = board.available_moves_filtered(|p| p == Piece::King);
+
+        assert_eq!(moves.len(), 10);
+        assert!(moves.iter().all(|m| (m.x, m.y) == (0, 5)));
+    }
+
+    #[test]
+    fn disambiguate_moves() {
+        let mut board = Board::empty();
+        board.place_piece(Piece::Attacker, 1, 4);
+        board.place_piece(Piece::Attacker, 6, 4);
+        board.place_piece(Piece::Attacker, 3, 8);
+        board.place_piece(Piece::Defender, 3, 1);
+
+        // only one attacker can reach d4
+        assert_eq!(board.disambiguate(&Move::from(3, 8, 3, 7).unwrap()), "Ad4");
+
+        // the attackers on b7 and g7 can both reach d7
+        assert_eq!(board.disambiguate(&Move::from(1, 4, 3, 4).unwrap()), "Abd7");
+        assert_eq!(board.disambiguate(&Move::from(6, 4, 3, 4).unwrap()), "Agd7");
+
+        // the attackers on b7 and d3 can both reach b3, but they share no file or rank
+        assert_eq!(board.disambiguate(&Move::from(3, 8, 1, 8).unwrap()), "Adb3");
+
+        // pieces of another kind do not need disambiguation
+        assert_eq!(board.disambiguate(&Move::from(3, 1, 3, 4).unwrap()), "Dd7");
+
+        // and the notation can be read back
+        for (x, y, to_x, to_y) in [(3, 8, 3, 7), (1, 4, 3, 4), (6, 4, 3, 4), (3, 8, 1, 8)] {
+            let m = Move::from(x, y, to_x, to_y).unwrap();
+            let parsed = board.parse_move(&board.disambiguate(&m)).unwrap();
+            assert!(parsed.same_path(&m));
+        }
+        // but not without the start tile when it is needed
+        assert_eq!(
+            board.parse_move("Ad7").unwrap_err(),
+            HnefataflError::InvalidNotation
+        );
+        assert_eq!(
+            board.parse_move("Da2").unwrap_err(),
+            HnefataflError::InvalidNotation
+        );
+    }
 }
 // }}}
@@ -1,7 +1,15 @@
 extern crate log;
 extern crate nom;
 
+#[cfg(feature = "ai")]
+pub mod ai;
 pub mod command;
+pub mod eval;
+pub mod game;
 pub mod hnefatafl;
+pub mod opening;
+pub mod search;
+#[cfg(feature = "svg")]
+pub mod svg;
 
 pub use hnefatafl::*;
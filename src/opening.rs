@@ -0,0 +1,72 @@
+use crate::{Board, CompactMove};
+
+/// A move as `(x, y, to_x, to_y)`
+type Path = (i32, i32, i32, i32);
+
+/// Named openings, as the moves that lead to them from the starting position
+const OPENINGS: &[(&str, &[Path])] = &[
+    ("Corner block", &[(3, 0, 3, 2)]),
+    ("Flank advance", &[(0, 7, 4, 7)]),
+    (
+        "Flank advance, defender sortie",
+        &[(0, 7, 4, 7), (5, 3, 8, 3)],
+    ),
+];
+
+/// Name the opening played in a game, from the moves of the game
+///
+/// The position reached is compared rather than the moves themselves, so openings reached by
+/// moving in another order are recognized as well. Positions are compared by their
+/// [`Board::zobrist`] hash, which covers the side to move. The longest matching opening is
+/// returned.
+pub fn classify_opening(moves: &[CompactMove]) -> Option<&'static str> {
+    let longest = OPENINGS.iter().map(|(_, o)| o.len()).max().unwrap_or(0);
+
+    // the hashes of the positions of the game after each move, up to the longest opening
+    let mut board = Board::new();
+    let mut positions = vec![board.zobrist()];
+    for m in moves.iter().take(longest) {
+        if board.do_move(&(*m).into()).is_err() {
+            break;
+        }
+        positions.push(board.zobrist());
+    }
+
+    OPENINGS
+        .iter()
+        .filter(|(_, opening)| {
+            let mut board = Board::new();
+            for &(x, y, to_x, to_y) in opening.iter() {
+                if board.move_piece(x, y, to_x, to_y).is_err() {
+                    return false;
+                }
+            }
+
+            positions.get(opening.len()) == Some(&board.zobrist())
+        })
+        .max_by_key(|(_, opening)| opening.len())
+        .map(|(name, _)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Move;
+
+    #[test]
+    fn recognize_opening() {
+        let moves = [
+            Move::from(0, 7, 4, 7).unwrap().compact(),
+            Move::from(5, 3, 8, 3).unwrap().compact(),
+            Move::from(10, 3, 9, 3).unwrap().compact(),
+        ];
+
+        assert_eq!(
+            classify_opening(&moves),
+            Some("Flank advance, defender sortie")
+        );
+        assert_eq!(classify_opening(&moves[..1]), Some("Flank advance"));
+        assert_eq!(classify_opening(&moves[2..]), None);
+        assert_eq!(classify_opening(&[]), None);
+    }
+}
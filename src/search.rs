@@ -0,0 +1,234 @@
+use std::time::{Duration, Instant};
+
+use crate::hnefatafl::Color;
+use crate::{Board, GameResult, Move, Turn};
+
+/// The score of a won game, larger than any evaluation should return
+///
+/// Wins found sooner score a little higher, so the quickest win is preferred
+pub const WIN_SCORE: i32 = 1_000_000;
+
+/// Search the moves of the side to move with alpha-beta pruning, `depth` plies deep
+///
+/// `eval` scores a position from the point of view of the defenders (white), so positive
+/// scores are good for white and negative scores good for black. The returned score is from
+/// the point of view of the side to move, along with the best move found. No move is returned
+/// if `depth` is 0 or the game is over.
+pub fn alpha_beta<const N: usize>(
+    board: &Board<N>,
+    depth: u32,
+    eval: &dyn Fn(&Board<N>) -> i32,
+) -> (Option<Move>, i32) {
+    // without a deadline the search is never stopped
+    search_root(board, depth, eval, None).unwrap()
+}
+
+/// Search deeper and deeper until `max_millis` milliseconds have passed
+///
+/// Returns the best move of the deepest search that was finished, see [`alpha_beta`]. The
+/// search of one ply deep is always finished, so a move is returned whenever there is one.
+/// Returns `None` if the game is over.
+pub fn search_timed<const N: usize>(
+    board: &Board<N>,
+    max_millis: u64,
+    eval: &dyn Fn(&Board<N>) -> i32,
+) -> Option<Move> {
+    if board.is_game_over() {
+        return None;
+    }
+
+    let deadline = Instant::now() + Duration::from_millis(max_millis);
+
+    let (mut best, value) = alpha_beta(board, 1, eval);
+    if value.abs() >= WIN_SCORE {
+        return best;
+    }
+
+    for depth in 2.. {
+        match search_root(board, depth, eval, Some(deadline)) {
+            Some((Some(m), value)) => {
+                best = Some(m);
+                // a won or lost game will not change by searching deeper
+                if value.abs() >= WIN_SCORE {
+                    break;
+                }
+            }
+            // out of time, or no moves to search
+            Some((None, _)) | None => break,
+        }
+    }
+
+    best
+}
+
+/// Search the moves from the root, stopping between two moves once `deadline` has passed
+///
+/// Returns `None` if the search was stopped before all moves were searched
+fn search_root<const N: usize>(
+    board: &Board<N>,
+    depth: u32,
+    eval: &dyn Fn(&Board<N>) -> i32,
+    deadline: Option<Instant>,
+) -> Option<(Option<Move>, i32)> {
+    let side = board.get_turn();
+    let mut board = board.clone();
+
+    if depth == 0 || board.is_game_over() {
+        return Some((None, score(&board, depth, side, eval)));
+    }
+
+    let mut best = None;
+    let mut alpha = -WIN_SCORE - depth as i32 - 1;
+    let beta = -alpha;
+
+    for m in board.available_moves() {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return None;
+        }
+
+        if board.do_move(&m).is_err() {
+            continue;
+        }
+        let value = -negamax(&mut board, depth - 1, -beta, -alpha, side.opposite(), eval);
+        // the move was just performed, so there is something to undo
+        board.undo().unwrap();
+
+        if value > alpha {
+            alpha = value;
+            best = Some(m);
+        }
+    }
+
+    Some((best, alpha))
+}
+
+/// The score of the position for `side`, the side to move, searched `depth` plies deep
+fn negamax<const N: usize>(
+    board: &mut Board<N>,
+    depth: u32,
+    mut alpha: i32,
+    beta: i32,
+    side: Turn,
+    eval: &dyn Fn(&Board<N>) -> i32,
+) -> i32 {
+    if depth == 0 || board.is_game_over() {
+        return score(board, depth, side, eval);
+    }
+
+    let moves = board.available_moves();
+    if moves.is_empty() {
+        return score(board, 0, side, eval);
+    }
+
+    for m in moves {
+        if board.do_move(&m).is_err() {
+            continue;
+        }
+        let value = -negamax(board, depth - 1, -beta, -alpha, side.opposite(), eval);
+        board.undo().unwrap();
+
+        alpha = alpha.max(value);
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    alpha
+}
+
+/// Score a position without searching any further, for `side`
+///
+/// `depth` is the number of plies left, so that wins with more plies left score higher
+fn score<const N: usize>(
+    board: &Board<N>,
+    depth: u32,
+    side: Turn,
+    eval: &dyn Fn(&Board<N>) -> i32,
+) -> i32 {
+    match board.result() {
+        GameResult::Draw => 0,
+        GameResult::Ongoing => match side {
+            Turn::White => eval(board),
+            Turn::Black => -eval(board),
+        },
+        result if result == GameResult::win_for(side) => WIN_SCORE + depth as i32,
+        _ => -WIN_SCORE - depth as i32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BoardBuilder, Piece};
+
+    fn material(board: &Board) -> i32 {
+        board.material(Turn::White) - board.material(Turn::Black)
+    }
+
+    #[test]
+    fn finds_winning_move() {
+        let mut board = Board::new();
+        // surround the king on three sides, the attacker on (0, 3) can close the fourth
+        board.move_piece_uncheced(5, 5, 2, 2);
+        board.move_piece_uncheced(3, 0, 2, 1);
+        board.move_piece_uncheced(4, 0, 1, 2);
+        board.move_piece_uncheced(5, 0, 3, 2);
+
+        let (best, value) = alpha_beta(&board, 1, &material);
+        let (_, outcome) = board.step(&best.unwrap()).unwrap();
+        assert_eq!(outcome, GameResult::AttackerWin);
+        assert!(value >= WIN_SCORE);
+
+        // the king can reach a corner
+        let board = BoardBuilder::new()
+            .piece(Piece::King, 0, 5)
+            .piece(Piece::Attacker, 7, 7)
+            .turn(Turn::White)
+            .build()
+            .unwrap();
+        let (best, value) = alpha_beta(&board, 1, &material);
+        let (_, outcome) = board.step(&best.unwrap()).unwrap();
+        assert_eq!(outcome, GameResult::DefenderWin);
+        assert!(value >= WIN_SCORE);
+    }
+
+    #[test]
+    fn returns_legal_moves() {
+        let mut board = Board::new();
+        for depth in 1..=2 {
+            let (best, _) = alpha_beta(&board, depth, &material);
+            let best = best.unwrap();
+            assert!(board.available_moves().contains(&best));
+            board.do_move(&best).unwrap();
+        }
+
+        // black is to move, and has 12 pieces more
+        assert_eq!(alpha_beta(&Board::new(), 0, &material), (None, 12));
+    }
+
+    #[test]
+    fn timed_search_keeps_to_budget() {
+        let board = Board::new();
+
+        let start = Instant::now();
+        let best = search_timed(&board, 200, &material).unwrap();
+        let elapsed = start.elapsed();
+
+        assert!(board.available_moves().contains(&best));
+        // the search is only stopped between the moves of the root
+        assert!(elapsed < Duration::from_secs(1), "took {:?}", elapsed);
+
+        // one ply is always searched
+        assert!(search_timed(&board, 0, &material).is_some());
+    }
+
+    #[test]
+    fn timed_search_of_finished_game() {
+        let mut board = Board::new();
+        board.set_result(GameResult::Draw);
+
+        let start = Instant::now();
+        assert_eq!(search_timed(&board, 50, &material), None);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt::Write;
+
+use crate::{Board, Move, Piece};
+
+/// Options for [`Board::to_svg`]
+#[derive(Debug, Clone)]
+pub struct SvgOptions {
+    /// Width and height of a single tile, in pixels
+    pub tile_size: u32,
+    /// Highlight the start and destination of this move
+    pub last_move: Option<Move>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            tile_size: 40,
+            last_move: None,
+        }
+    }
+}
+
+impl<const N: usize> Board<N> {
+    /// Render the board as an SVG image
+    pub fn to_svg(&self, opts: SvgOptions) -> String {
+        let size = opts.tile_size;
+        let mut svg = String::new();
+
+        // Writing to a String never fails, so the results are ignored
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="0 0 {0} {0}">"#,
+            size * N as u32
+        );
+
+        // tiles, with the restricted tiles shaded
+        for y in 0..N as i32 {
+            for x in 0..N as i32 {
+                let fill = if self.is_fortress(x, y) {
+                    "#a0a0a0"
+                } else {
+                    "#e8d8b0"
+                };
+                let _ = writeln!(
+                    svg,
+                    r##"<rect x="{}" y="{}" width="{size}" height="{size}" fill="{fill}" stroke="#404040"/>"##,
+                    x as u32 * size,
+                    y as u32 * size,
+                );
+            }
+        }
+
+        if let Some(m) = &opts.last_move {
+            for (x, y) in [(m.x, m.y), m.destination()] {
+                let _ = writeln!(
+                    svg,
+                    r##"<rect x="{}" y="{}" width="{size}" height="{size}" fill="#f0e060" fill-opacity="0.6"/>"##,
+                    x as u32 * size,
+                    y as u32 * size,
+                );
+            }
+        }
+
+        for (x, y, piece) in self {
+            let (class, fill) = match piece {
+                Piece::King => ("king", "#f0f0f0"),
+                Piece::Defender => ("defender", "#ffffff"),
+                Piece::Attacker => ("attacker", "#202020"),
+            };
+            let radius = if piece == Piece::King {
+                size * 2 / 5
+            } else {
+                size / 3
+            };
+            let _ = writeln!(
+                svg,
+                r##"<circle class="{class}" cx="{}" cy="{}" r="{radius}" fill="{fill}" stroke="#000000"/>"##,
+                x as u32 * size + size / 2,
+                y as u32 * size + size / 2,
+            );
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svg_contains_all_pieces() {
+        let svg = Board::new().to_svg(SvgOptions::default());
+
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<circle").count(), 37);
+        assert_eq!(svg.matches(r#"class="attacker""#).count(), 24);
+        assert_eq!(svg.matches(r#"class="king""#).count(), 1);
+    }
+
+    #[test]
+    fn svg_highlights_last_move() {
+        let svg = Board::new().to_svg(SvgOptions {
+            last_move: Some(Move::from(0, 7, 4, 7).unwrap()),
+            ..Default::default()
+        });
+
+        assert_eq!(svg.matches("#f0e060").count(), 2);
+    }
+}